@@ -0,0 +1,109 @@
+//! Events that are dispatched to the forms and the context their handlers
+//! run in.
+
+use collections::Vec;
+use collections::boxed::Box;
+
+use application::app::App;
+use application::view::View;
+use forms::theme::Theme;
+use move_things::gesture::Gesture;
+use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
+
+/// Positions are in screen coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Pressed { x: i32, y: i32 },
+    Released { x: i32, y: i32 },
+    Clicked { x: i32, y: i32 },
+    // sent to the pressed form while its finger moves
+    Moved { x: i32, y: i32 },
+    DragStart { x: i32, y: i32 },
+    DragMove { x: i32, y: i32, dx: i32, dy: i32 },
+    DragEnd { x: i32, y: i32 },
+    FocusGained,
+    FocusLost,
+    // sent to the form at the position of the gesture
+    Gesture(Gesture),
+    // sent to the focused form
+    Key(Key),
+}
+
+/// Text input, e.g. from the on-screen keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Backspace,
+    Enter,
+}
+
+/// Changes of the application requested by a handler, the `App` applies
+/// them after the event was dispatched.
+pub enum Action {
+    SetActiveView(View),
+    SetTheme(Theme),
+    SendKey(Key),
+    Run(Box<FnMut(&mut App)>),
+}
+
+pub struct EventContext<'a> {
+    dirty: &'a mut DirtyRegion,
+    actions: &'a mut Vec<Action>,
+    propagation_stopped: bool,
+}
+
+impl<'a> EventContext<'a> {
+    pub fn new(dirty: &'a mut DirtyRegion, actions: &'a mut Vec<Action>) -> EventContext<'a> {
+        EventContext {
+            dirty: dirty,
+            actions: actions,
+            propagation_stopped: false,
+        }
+    }
+
+    // Redraws the area with the next frame.
+    pub fn invalidate(&mut self, bounding_box: &BoundingBox) {
+        self.dirty.add(bounding_box);
+    }
+
+    pub fn get_dirty_region(&mut self) -> &mut DirtyRegion {
+        self.dirty
+    }
+
+    // Keeps the event from bubbling up to the ancestors of the handling form.
+    pub fn stop_propagation(&mut self) {
+        self.propagation_stopped = true;
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation_stopped
+    }
+
+    // Called before an event is dispatched.
+    pub fn start_propagation(&mut self) {
+        self.propagation_stopped = false;
+    }
+
+    pub fn set_active_view(&mut self, view: View) {
+        self.actions.push(Action::SetActiveView(view));
+    }
+
+    // Reskins the active view and the ones shown later.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.actions.push(Action::SetTheme(theme));
+    }
+
+    // Types `key` into the focused form.
+    pub fn send_key(&mut self, key: Key) {
+        self.actions.push(Action::SendKey(key));
+    }
+
+    // Runs `action` with the app once the event was handled, e.g. to change
+    // forms outside of the handling one.
+    pub fn run<F>(&mut self, action: F)
+        where F: FnMut(&mut App) + 'static
+    {
+        self.actions.push(Action::Run(Box::new(action)));
+    }
+}
@@ -1,16 +1,243 @@
-use core::ops::DerefMut;
+use collections::Vec;
 
+use action::event::Event;
+use action::event::EventContext;
 use forms::form::Form;
+use input::TouchPoint;
 
-pub fn walk(root: &mut Form, x: i32, y: i32) {
-    for mut child in root.get_children() {
-        walk(child.deref_mut(), x, y);
+// A finger on the panel and the clickable form it went down on.
+struct Contact {
+    id: u8,
+    // path of child indices from the root, None if the touch started
+    // outside of any clickable form or in a view that is gone by now.
+    // Events bubble from this form up to its clickable ancestors.
+    path: Option<Vec<usize>>,
+    x: i32,
+    y: i32,
+    // position of the form when it was pressed
+    origin: (i32, i32),
+    // the pressed form stopped the propagation of the press and follows the
+    // finger itself, nothing is dragged with it
+    captured: bool,
+}
+
+/// Turns the polled touches into press, release and click events.
+///
+/// A form is pressed when a finger goes down on it and gets a click when the
+/// same finger is lifted while still inside of it, no matter how long it was
+/// held. Only the topmost form under the finger is pressed, the events then
+/// bubble up to its ancestors until a handler stops the propagation. While
+/// the finger moves, the pressed form alone gets move events. A form that
+/// stops the propagation of the press keeps the finger to itself, it is not
+/// dragged and neither are its ancestors.
+/// Pressing another form moves the focus, unless that form does not take
+/// it.
+pub struct Walker {
+    contacts: Vec<Contact>,
+    focused: Option<Vec<usize>>,
+}
+
+impl Walker {
+    pub fn new() -> Walker {
+        Walker {
+            contacts: Vec::new(),
+            focused: None,
+        }
+    }
+
+    /// Forgets the forms of the current contacts, e.g. after the view
+    /// changed. The fingers have to be lifted before they press again.
+    pub fn reset(&mut self) {
+        for contact in &mut self.contacts {
+            contact.path = None;
+            contact.captured = false;
+        }
+        self.focused = None;
+    }
+
+    pub fn update(&mut self, root: &mut Form, touches: &[TouchPoint], ctx: &mut EventContext) {
+        // lifted fingers release their form
+        let mut i = 0;
+        while i < self.contacts.len() {
+            if touches.iter().any(|t| t.id == self.contacts[i].id) {
+                i += 1;
+                continue;
+            }
+
+            let contact = self.contacts.remove(i);
+            if let Some(path) = contact.path {
+                let (x, y) = (contact.x, contact.y);
+                bubble(root, &path, &Event::Released { x: x, y: y }, ctx);
+
+                // a form that was moved or scrolled under the finger was
+                // dragged and not clicked
+                let inside = match form_at_path(root, &path) {
+                    Some(form) => {
+                        let bounding_box = form.get_bounding_box();
+                        bounding_box.is_in_bound(x, y) &&
+                        (bounding_box.x, bounding_box.y) == contact.origin
+                    }
+                    None => false,
+                };
+                if inside {
+                    bubble(root, &path, &Event::Clicked { x: x, y: y }, ctx);
+                }
+            }
+        }
+
+        for touch in touches {
+            if let Some(contact) = self.contacts.iter_mut().find(|c| c.id == touch.id) {
+                let moved = contact.x != touch.x || contact.y != touch.y;
+                contact.x = touch.x;
+                contact.y = touch.y;
+                if moved {
+                    if let Some(ref path) = contact.path {
+                        dispatch(root, path, &Event::Moved { x: touch.x, y: touch.y }, ctx);
+                    }
+                }
+                continue;
+            }
+
+            // a new finger, the nearest clickable form of the topmost hit
+            // handles it
+            let path = target_at(root, touch.x, touch.y);
+            let mut origin = (0, 0);
+            let mut captured = false;
+            if let Some(ref path) = path {
+                let mut takes_focus = false;
+                if let Some(form) = form_at_path(root, path) {
+                    takes_focus = form.takes_focus();
+                    let bounding_box = form.get_bounding_box();
+                    origin = (bounding_box.x, bounding_box.y);
+                }
+                if takes_focus {
+                    self.focus(root, path, ctx);
+                }
+                bubble(root, path, &Event::Pressed { x: touch.x, y: touch.y }, ctx);
+                captured = ctx.is_propagation_stopped();
+            }
+
+            self.contacts.push(Contact {
+                id: touch.id,
+                path: path,
+                x: touch.x,
+                y: touch.y,
+                origin: origin,
+                captured: captured,
+            });
+        }
+    }
+
+    /// True if the form pressed by the finger with the given id follows it
+    /// itself, so no form is to be dragged by it.
+    pub fn is_captured(&self, id: u8) -> bool {
+        self.contacts.iter().any(|c| c.id == id && c.captured)
+    }
+
+    /// Sends `event` to the focused form, if there is one.
+    pub fn dispatch_to_focused(&self, root: &mut Form, event: &Event, ctx: &mut EventContext) {
+        if let Some(ref path) = self.focused {
+            dispatch(root, path, event, ctx);
+        }
+    }
+
+    fn focus(&mut self, root: &mut Form, path: &Vec<usize>, ctx: &mut EventContext) {
+        if self.focused.as_ref() == Some(path) {
+            return;
+        }
+
+        if let Some(ref old) = self.focused {
+            dispatch(root, old, &Event::FocusLost, ctx);
+        }
+        dispatch(root, path, &Event::FocusGained, ctx);
+        self.focused = Some(path.clone());
+    }
+}
+
+/// Returns the form reached by following the child indices of `path`.
+pub fn form_at_path<'a>(root: &'a mut Form, path: &[usize]) -> Option<&'a mut Form> {
+    match path.split_first() {
+        None => Some(root),
+        Some((&index, rest)) => {
+            match root.get_children().nth(index) {
+                Some(child) => form_at_path(child, rest),
+                None => None,
+            }
+        }
+    }
+}
+
+/// Sends `event` to the clickable form at `path` only.
+pub fn dispatch(root: &mut Form, path: &[usize], event: &Event, ctx: &mut EventContext) {
+    if let Some(form) = form_at_path(root, path) {
+        if let Some(clickable) = form.is_clickable() {
+            clickable.handle_event(event, ctx);
+        }
+    }
+}
+
+/// Sends `event` to the clickable form at `path` and then to its clickable
+/// ancestors, up to the root or until a handler stops the propagation.
+pub fn bubble(root: &mut Form, path: &[usize], event: &Event, ctx: &mut EventContext) {
+    ctx.start_propagation();
+    for len in (0..path.len() + 1).rev() {
+        dispatch(root, &path[..len], event, ctx);
+        if ctx.is_propagation_stopped() {
+            break;
+        }
     }
+}
+
+/// Returns the path of the topmost form containing the point. Children are
+/// drawn after their parent and later siblings over earlier ones, so they
+/// are checked in reverse order. The root itself is returned as an empty
+/// path, None if the point is outside of everything.
+pub fn hit_test(root: &mut Form, x: i32, y: i32) -> Option<Vec<usize>> {
+    let mut path = Vec::new();
+    if hit_test_into(root, x, y, &mut path) {
+        Some(path)
+    } else {
+        None
+    }
+}
 
-    if root.get_bounding_box().is_in_bound(x, y) {
-        match root.is_clickable() {
-            None => (),
-            Some(clickable) => clickable.click(),
+// Children outside of their parent are not hit, e.g. scrolled out content.
+fn hit_test_into(root: &mut Form, x: i32, y: i32, path: &mut Vec<usize>) -> bool {
+    if !root.get_bounding_box().is_in_bound(x, y) {
+        return false;
+    }
+
+    {
+        let children: Vec<&mut Form> = root.get_children().collect();
+        for (i, child) in children.into_iter().enumerate().rev() {
+            path.push(i);
+            if hit_test_into(child, x, y, path) {
+                return true;
+            }
+            path.pop();
+        }
+    }
+    true
+}
+
+/// Returns the path of the nearest clickable form of the topmost hit, the
+/// form that handles events at the point first.
+pub fn target_at(root: &mut Form, x: i32, y: i32) -> Option<Vec<usize>> {
+    hit_test(root, x, y).and_then(|path| clickable_ancestor(root, path))
+}
+
+// Shortens the path to the nearest form that is clickable itself.
+fn clickable_ancestor(root: &mut Form, mut path: Vec<usize>) -> Option<Vec<usize>> {
+    loop {
+        let clickable = match form_at_path(root, &path) {
+            Some(form) => form.is_clickable().is_some(),
+            None => false,
+        };
+        if clickable {
+            return Some(path);
+        }
+        if path.pop().is_none() {
+            return None;
         }
     }
 }
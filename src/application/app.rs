@@ -1,19 +1,174 @@
+use collections::Vec;
+use collections::boxed::Box;
+
+use action::event::Action;
+use action::event::Event;
+use action::event::EventContext;
+use action::event::Key;
+use action::walker;
+use action::walker::Walker;
 use application::view::View;
+use draw::DrawTarget;
+use forms::form::Form;
+use forms::theme::Theme;
+use input::TouchPoint;
+use move_things::gesture::GestureConfig;
+use move_things::gesture::GestureRecognizer;
+use move_things::swipe::TouchHistory;
+use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
 
 pub struct App {
     active_view: View,
+    dirty: DirtyRegion,
+    walker: Walker,
+    gestures: GestureRecognizer,
+    touch_history: TouchHistory,
+    // applied to every view that is shown
+    theme: Theme,
+    // Frames are composed here and copied to the display at vsync if set.
+    back_buffer: Option<Box<DrawTarget>>,
 }
 
 impl App {
     pub fn new(view: View) -> App {
-        App { active_view: view }
+        let mut app = App {
+            active_view: view,
+            dirty: DirtyRegion::new(),
+            walker: Walker::new(),
+            gestures: GestureRecognizer::new(),
+            touch_history: TouchHistory::new(),
+            theme: Theme::dark(),
+            back_buffer: None,
+        };
+        app.active_view.apply_theme(&app.theme);
+        app.invalidate_view();
+        app
+    }
+
+    /// Renders double-buffered: every frame is composed in `back_buffer` and
+    /// only finished areas are copied to the display, so moving forms do not
+    /// flicker. The back buffer needs the size of the display.
+    pub fn with_back_buffer(view: View, back_buffer: Box<DrawTarget>) -> App {
+        let mut app = App::new(view);
+        app.back_buffer = Some(back_buffer);
+        app
     }
 
     pub fn set_active_view(&mut self, view: View) {
         self.active_view = view;
+        self.walker.reset();
+        self.touch_history.reset();
+        self.active_view.apply_theme(&self.theme);
+        self.invalidate_view();
+    }
+
+    pub fn get_theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.active_view.apply_theme(&self.theme);
+        self.invalidate_view();
     }
 
     pub fn get_active_view(&mut self) -> &mut View {
         &mut self.active_view
     }
+
+    pub fn get_gesture_config(&mut self) -> &mut GestureConfig {
+        self.gestures.get_config()
+    }
+
+    /// Types `key` into the focused form, e.g. the text field the keyboard
+    /// is used for.
+    pub fn send_key(&mut self, key: Key) {
+        let mut actions = Vec::new();
+        {
+            let mut ctx = EventContext::new(&mut self.dirty, &mut actions);
+            self.walker.dispatch_to_focused(&mut self.active_view, &Event::Key(key), &mut ctx);
+        }
+        self.apply(actions);
+    }
+
+    pub fn invalidate(&mut self, bounding_box: &BoundingBox) {
+        self.dirty.add(bounding_box);
+    }
+
+    fn invalidate_view(&mut self) {
+        let bounding_box = self.active_view.get_bounding_box().clone();
+        self.dirty.add(&bounding_box);
+    }
+
+    /// Feeds the touches of one main loop iteration to the active view.
+    pub fn update(&mut self, ticks: usize, touches: &[TouchPoint]) {
+        let mut actions = Vec::new();
+        {
+            let mut ctx = EventContext::new(&mut self.dirty, &mut actions);
+            self.walker.update(&mut self.active_view, touches, &mut ctx);
+
+            for gesture in self.gestures.update(ticks, touches) {
+                let (x, y) = gesture.position();
+                if let Some(path) = walker::target_at(&mut self.active_view, x, y) {
+                    walker::bubble(&mut self.active_view,
+                                   &path,
+                                   &Event::Gesture(gesture),
+                                   &mut ctx);
+                }
+            }
+
+            self.touch_history.update(touches);
+            for touch in touches {
+                if self.walker.is_captured(touch.id) {
+                    self.touch_history.capture(touch.id);
+                }
+            }
+            self.touch_history.check_for_object_moves(&mut self.active_view, &mut ctx);
+        }
+        self.active_view.tick(ticks, &mut self.dirty);
+
+        self.apply(actions);
+    }
+
+    fn apply(&mut self, actions: Vec<Action>) {
+        for action in actions {
+            match action {
+                Action::SetActiveView(view) => self.set_active_view(view),
+                Action::SetTheme(theme) => self.set_theme(theme),
+                Action::SendKey(key) => self.send_key(key),
+                Action::Run(mut action) => action(self),
+            }
+        }
+    }
+
+    /// Redraws everything that was invalidated since the last frame.
+    pub fn render(&mut self, target: &mut DrawTarget) {
+        match self.back_buffer {
+            None => redraw_dirty(&mut self.active_view, &mut self.dirty, target),
+            Some(ref mut back_buffer) => {
+                let rects = self.dirty.rects().to_vec();
+                redraw_dirty(&mut self.active_view, &mut self.dirty, &mut **back_buffer);
+
+                if rects.is_empty() {
+                    return;
+                }
+
+                target.wait_for_vsync();
+                for rect in &rects {
+                    target.copy_area(&**back_buffer, rect);
+                }
+            }
+        }
+    }
+}
+
+/// Clears the dirty areas and redraws the forms of `root` intersecting them.
+pub fn redraw_dirty(root: &mut Form, dirty: &mut DirtyRegion, target: &mut DrawTarget) {
+    for rect in dirty.take() {
+        target.clear_area(&rect);
+        target.push_clip(&rect);
+        root.redraw(target, &rect);
+        target.pop_clip();
+    }
 }
@@ -1,37 +1,43 @@
 use collections::boxed::Box;
 use core::iter;
-use stm32f7::lcd::Color;
 
+use draw::Color;
+use draw::display;
+use draw::DrawTarget;
 use draw::fill_rectangle;
 use forms::form::Form;
+use forms::form::draw_child;
+use forms::form::redraw_child;
 use forms::form::Clickable;
+use forms::theme::Theme;
 use util::bounding_box::BoundingBox;
-use util::sizes;
+use util::dirty_region::DirtyRegion;
 
 pub struct View {
     bounding_box: BoundingBox,
     outer_bounding_box: BoundingBox,
     child: Box<Form>,
     movable: bool,
+    background: Color,
 }
 
 impl View {
     pub fn new(child: Box<Form>) -> View {
         View {
-            bounding_box: BoundingBox {
-                x: 0,
-                y: 0,
-                width: sizes::RES_X,
-                height: sizes::RES_Y,
-            },
-            outer_bounding_box: BoundingBox {
-                x: 0,
-                y: 0,
-                width: sizes::RES_X,
-                height: sizes::RES_Y,
-            },
+            bounding_box: display::current().get_bounding_box(),
+            outer_bounding_box: display::current().get_bounding_box(),
             child: child,
             movable: false,
+            background: Theme::dark().background,
+        }
+    }
+
+    fn fill_background(&self, target: &mut DrawTarget, area: &BoundingBox) {
+        if self.background.alpha == 0 {
+            return;
+        }
+        if let Some(area) = self.bounding_box.intersection(area) {
+            fill_rectangle(target, area.x, area.y, area.width, area.height, self.background);
         }
     }
 }
@@ -63,20 +69,22 @@ impl Form for View {
         self.movable = value;
     }
 
-    fn clear(&self) -> () {
-        fill_rectangle(self.bounding_box.x,
-                       self.bounding_box.y,
-                       self.bounding_box.width,
-                       self.bounding_box.height,
-                       Color::rgba(0, 0, 0, 0));
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box.with_outline());
     }
 
-    fn draw(&self) -> () {
-        self.child.draw();
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        self.fill_background(target, &self.bounding_box);
+        draw_child(target, &self.bounding_box, &*self.child);
     }
 
-    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool) {
-        self.clear();
+    fn redraw(&mut self, target: &mut DrawTarget, region: &BoundingBox) -> () {
+        self.fill_background(target, region);
+        redraw_child(target, &self.bounding_box, &mut *self.child, region);
+    }
+
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box);
 
         let outer_if_top = if top {
             Some(&self.outer_bounding_box)
@@ -89,8 +97,13 @@ impl Form for View {
 
         self.child
             .set_outer_bounding_box(self.bounding_box.clone());
-        self.child.move_form(delta_x, delta_y, false);
+        self.child.move_form(delta_x, delta_y, false, dirty);
+
+        dirty.add(&self.bounding_box);
+    }
 
-        self.draw();
+    fn apply_theme(&mut self, theme: &Theme) -> () {
+        self.background = theme.background;
+        self.child.apply_theme(theme);
     }
 }
@@ -1,14 +1,39 @@
+use alloc::rc::Rc;
 use collections::boxed::Box;
+use core::cell::RefCell;
 
 use layout::HorizontalLayout;
 use layout::VerticalLayout;
 use layout::MoveBox;
+use layout::ScrollView;
 use forms::form::Form;
 use forms::button::Button;
+use forms::checkbox::Checkbox;
+use forms::image::Image;
+use forms::image::Scaling;
+use forms::keyboard::Keyboard;
+use forms::keyboard::KeyboardLayout;
+use forms::progress_bar::ProgressBar;
+use forms::radio_group::RadioGroup;
+use forms::slider::Slider;
+use forms::switch::Switch;
+use forms::text_field::TextField;
 use forms::label::Label;
 use util::bounding_box::BoundingBox;
-use util::sizes;
 use application::view::View;
+use draw::Color;
+use draw::PixelFormat;
+use draw::display;
+use font;
+use font::Font;
+use font::bdf;
+use font::layout::HorizontalAlign;
+use font::layout::VerticalAlign;
+use image::bmp;
+use image::qoi;
+use image::raw;
+use forms::theme::Style;
+use forms::theme::Theme;
 
 fn bb(x: i32, y:i32, width: i32, height: i32) -> BoundingBox {
     BoundingBox {
@@ -24,7 +49,7 @@ fn bb_def() -> BoundingBox {
 }
 
 fn bb_screen() -> BoundingBox {
-    bb(0, 0, sizes::RES_X, sizes::RES_Y)
+    display::current().get_bounding_box()
 }
 
 fn empty() -> Box<HorizontalLayout> {
@@ -32,29 +57,37 @@ fn empty() -> Box<HorizontalLayout> {
 }
 
 
-fn clicked(form: &mut Button) {
-        let label = Label::new(BoundingBox{
-            x: 0,
-            y: 0,
-            width: 10,
-            height: 10,
-        }, "clicked!");
+/// State shared by the demo views.
+#[derive(Clone, Copy)]
+pub struct Settings {
+    pub language: &'static str,
+    pub auto_capitalization: bool,
+    pub double_space_period: bool,
+    pub sound_on_keypress: bool,
+    pub popup_on_keypress: bool,
+    // in percent
+    pub brightness: i32,
+    pub volume: i32,
+}
 
-    for child in form.get_children() {
-        child.clear();
-        //child.draw();
-    }
-    form.set_child(Box::new(label));
+pub type SharedSettings = Rc<RefCell<Settings>>;
 
-    form.clear();
-    form.draw();
+pub fn settings() -> SharedSettings {
+    Rc::new(RefCell::new(Settings {
+                             language: "English",
+                             auto_capitalization: true,
+                             double_space_period: false,
+                             sound_on_keypress: false,
+                             popup_on_keypress: true,
+                             brightness: 80,
+                             volume: 50,
+                         }))
 }
 
 fn button(string: &'static str) -> Box<Button> {
     let label = Label::new(bb_def(), string);
     let mut button = Button::new(bb_def());
-    button.set_child(Box::new(label));
-    //button.set_action_on_click(clicked);
+    button.set_label(label);
     Box::new(button)
 }
 
@@ -69,39 +102,271 @@ fn middle(middle_form: Box<Form>) -> View {
     View::new(Box::new(f))
 }
 
-pub fn view_languages() -> View {
-    let mut f = VerticalLayout::new(bb_screen());
-    f.add_form(button("English"));
-    f.add_form(button("Français"));
-    f.add_form(button("Deutsch"));
-    f.add_form(button("Italiano"));
-    f.add_form(button("Português"));
-    f.add_form(button("Rust"));
-    middle(Box::new(f))
+const LANGUAGES: [&'static str; 6] = ["English", "Français", "Deutsch", "Italiano", "Português",
+                                      "Rust"];
+
+pub fn view_languages(settings: SharedSettings) -> View {
+    // the buttons keep their height and the list scrolls
+    let mut f = VerticalLayout::new(bb(0, 0, 10, LANGUAGES.len() as i32 * 60));
+    for &language in LANGUAGES.iter() {
+        let settings = settings.clone();
+        let mut language_button = button(language);
+        language_button.set_action_on_click(move |_, ctx| {
+            settings.borrow_mut().language = language;
+            ctx.set_active_view(view_settings(settings.clone()));
+        });
+        f.add_form(language_button);
+    }
+
+    let mut scroll_view = ScrollView::new(bb_def());
+    scroll_view.set_child(Box::new(f));
+    middle(Box::new(scroll_view))
+}
+
+// The built-in font with thicker strokes, parsed on first use.
+static mut BOLD: Option<&'static Font> = None;
+
+fn bold_font() -> &'static Font {
+    // the demo runs in one thread, so nothing else accesses BOLD meanwhile
+    unsafe {
+        if let Some(font) = BOLD {
+            return font;
+        }
+        // the asset is known to parse
+        let font = bdf::parse(include_str!("../../assets/bold.bdf")).unwrap().into_static();
+        BOLD = Some(font);
+        font
+    }
+}
+
+fn rusty_safety() -> Theme {
+    let button = Style {
+        background: Color::from_hex(0x3B2418),
+        border: Color::from_hex(0xB7410E),
+        pressed: Color::from_hex(0x6E2A0C),
+        text: Color::from_hex(0xF5DEB3),
+        font: bold_font(),
+        font_size: 1,
+        border_width: 3,
+        padding: 2,
+        corner_radius: 4,
+    };
+
+    Theme {
+        name: "Rusty Safety",
+        background: Color::from_hex(0x1E120C),
+        button: button,
+        label: button,
+        frame: Style { border_width: 1, ..button },
+    }
+}
+
+fn rusty_zero_cost() -> Theme {
+    let mut theme = Theme::dark();
+    theme.name = "Rusty Zero Cost";
+    theme.button.border = Color::from_hex(0xDEA584);
+    theme.button.border_width = 1;
+    theme.frame.border = Color::from_hex(0xDEA584);
+    theme
+}
+
+fn clippy() -> Theme {
+    let button = Style {
+        background: Color::from_hex(0xFFFFE8),
+        border: Color::from_hex(0x4A4A4A),
+        pressed: Color::from_hex(0xE6E6A0),
+        text: Color::from_hex(0x000000),
+        font: &font::BUILTIN,
+        font_size: 1,
+        border_width: 2,
+        padding: 6,
+        corner_radius: 12,
+    };
+
+    Theme {
+        name: "Clippy",
+        background: Color::from_hex(0xFFFFCC),
+        button: button,
+        label: button,
+        frame: Style { corner_radius: 0, ..button },
+    }
 }
 
 pub fn view_skins() -> View {
+    let skins: [(&'static str, fn() -> Theme); 5] =
+        [("Standard Dark Theme", Theme::dark),
+         ("Standard Light Theme", Theme::light),
+         ("Rusty Safety Theme", rusty_safety),
+         ("Rusty Zero Cost Theme ", rusty_zero_cost),
+         ("Clippy Theme ", clippy)];
+
     let mut f = VerticalLayout::new(bb_screen());
-    f.add_form(button("Standard Dark Theme"));
-    f.add_form(button("Standard Light Theme"));
-    f.add_form(button("Rusty Safety Theme"));
-    f.add_form(button("Rusty Zero Cost Theme "));
-    f.add_form(button("Clippy Theme "));
+    for &(name, theme) in skins.iter() {
+        let mut skin_button = button(name);
+        skin_button.set_action_on_click(move |_, ctx| ctx.set_theme(theme()));
+        f.add_form(skin_button);
+    }
     middle(Box::new(f))
 }
 
-pub fn view_settings() -> View {
+pub fn view_settings(settings: SharedSettings) -> View {
+    let current = *settings.borrow();
+    let selected = LANGUAGES.iter().position(|&language| language == current.language);
+    let mut language = RadioGroup::new(bb_def(), &LANGUAGES, selected.unwrap_or(0));
+    let mut auto_capitalization = Checkbox::new(bb_def(),
+                                                "Auto-capitalization",
+                                                current.auto_capitalization);
+    let mut double_space_period = Checkbox::new(bb_def(),
+                                                "Double-space period",
+                                                current.double_space_period);
+    let mut sound_on_keypress = Switch::new(bb_def(),
+                                            "Sound on keypress",
+                                            current.sound_on_keypress);
+    let mut popup_on_keypress = Switch::new(bb_def(),
+                                            "Popup on keypress",
+                                            current.popup_on_keypress);
+
+    let shared = settings.clone();
+    language.set_action_on_change(move |radio, _| {
+        shared.borrow_mut().language = LANGUAGES[radio.get_selected()];
+    });
+    let shared = settings.clone();
+    auto_capitalization.set_action_on_change(move |checkbox, _| {
+        shared.borrow_mut().auto_capitalization = checkbox.is_checked();
+    });
+    let shared = settings.clone();
+    double_space_period.set_action_on_change(move |checkbox, _| {
+        shared.borrow_mut().double_space_period = checkbox.is_checked();
+    });
+    let shared = settings.clone();
+    sound_on_keypress.set_action_on_change(move |switch, _| {
+        shared.borrow_mut().sound_on_keypress = switch.is_on();
+    });
+    popup_on_keypress.set_action_on_change(move |switch, _| {
+        settings.borrow_mut().popup_on_keypress = switch.is_on();
+    });
+
+    let mut toggles = VerticalLayout::new(bb_def());
+    toggles.add_form(Box::new(auto_capitalization));
+    toggles.add_form(Box::new(double_space_period));
+    toggles.add_form(Box::new(sound_on_keypress));
+    toggles.add_form(Box::new(popup_on_keypress));
+
+    // the languages on the left, the toggles on the right
+    let mut f = HorizontalLayout::new(bb_screen());
+    f.add_form(empty());
+    f.add_form(Box::new(language));
+    f.add_form(empty());
+    f.add_form(Box::new(toggles));
+    f.add_form(empty());
+    f.set_proportions(vec![1, 5, 1, 11, 1]);
+    View::new(Box::new(f))
+}
+
+pub fn view_controls(settings: SharedSettings) -> View {
+    let current = *settings.borrow();
+    let mut brightness = Slider::new(bb_def(), 0, 100, current.brightness).with_step(10);
+    let mut volume = Slider::new(bb_def(), 0, 100, current.volume);
+
+    let shared = settings.clone();
+    brightness.set_action_on_change(move |slider, _| {
+        shared.borrow_mut().brightness = slider.get_value();
+    });
+    volume.set_action_on_change(move |slider, _| {
+        settings.borrow_mut().volume = slider.get_value();
+    });
+
+    let mut loading = ProgressBar::new(bb_def(), 100);
+    loading.set_indeterminate(true);
+
     let mut f = VerticalLayout::new(bb_screen());
-    f.add_form(button("Auto-capitalization"));
-    f.add_form(button("Double-space period"));
-    f.add_form(button("Sound on keypress"));
-    f.add_form(button("Popup on keypress"));
+    f.add_form(empty());
+    f.add_form(control("Brightness", Box::new(brightness)));
+    f.add_form(control("Volume", Box::new(volume)));
+    f.add_form(control("Loading", Box::new(loading)));
+    f.add_form(empty());
     middle(Box::new(f))
 }
 
+// A control with its name right aligned on the left of it.
+fn control(name: &'static str, form: Box<Form>) -> Box<HorizontalLayout> {
+    let mut label = Label::new(bb_def(), name);
+    label.get_text_layout().horizontal = HorizontalAlign::Right;
+
+    let mut f = HorizontalLayout::new(bb_def());
+    f.add_form(Box::new(label));
+    f.add_form(empty());
+    f.add_form(form);
+    f.set_proportions(vec![4, 1, 8]);
+    Box::new(f)
+}
+
+pub fn view_keyboard(settings: SharedSettings) -> View {
+    let current = *settings.borrow();
+    let layout = match current.language {
+        "Deutsch" => KeyboardLayout::Qwertz,
+        "Français" => KeyboardLayout::Azerty,
+        _ => KeyboardLayout::Qwerty,
+    };
+    let mut keyboard = Keyboard::new(bb_def(), layout);
+    keyboard.set_popups(current.popup_on_keypress);
+    keyboard.set_auto_capitalization(current.auto_capitalization);
+    keyboard.set_double_space_period(current.double_space_period);
+
+    let mut text_field = TextField::new(bb_def());
+    text_field.set_placeholder("Tap here to type");
+
+    // the keyboard takes the lower part of the screen
+    let mut f = VerticalLayout::new(bb_screen());
+    f.add_form(Box::new(text_field));
+    f.add_form(Box::new(keyboard));
+    f.set_proportions(vec![1, 3]);
+    View::new(Box::new(f))
+}
+
+// An image with its name right above it and a margin below, the last form
+// of a layout would fill all of it.
+fn picture(image: Image, name: &'static str) -> Box<VerticalLayout> {
+    let mut label = Label::new(bb_def(), name);
+    label.get_text_layout().vertical = VerticalAlign::Bottom;
+
+    let mut f = VerticalLayout::new(bb_def());
+    f.add_form(Box::new(label));
+    f.add_form(Box::new(image));
+    f.add_form(empty());
+    f.set_proportions(vec![2, 8, 1]);
+    Box::new(f)
+}
+
+pub fn view_images() -> View {
+    // the assets are known to decode
+    let logo = qoi::decode(include_bytes!("../../assets/logo.qoi")).unwrap();
+    let check = bmp::decode(include_bytes!("../../assets/check.bmp")).unwrap();
+    let stripes = raw::decode(include_bytes!("../../assets/stripes.rgb565"),
+                              16,
+                              16,
+                              PixelFormat::Rgb565)
+        .unwrap();
+
+    // the check mark has a magenta background instead of an alpha channel
+    let mut f = HorizontalLayout::new(bb_screen());
+    f.add_form(picture(Image::new(bb_def(), logo), "QOI"));
+    f.add_form(picture(Image::new(bb_def(), check)
+                           .with_scaling(Scaling::None)
+                           .with_color_key(Color::from_hex(0xFF00FF)),
+                       "BMP"));
+    f.add_form(picture(Image::new(bb_def(), stripes).with_scaling(Scaling::Stretch),
+                       "RGB565"));
+    View::new(Box::new(f))
+}
+
 pub fn view_about() -> View {
+    let mut intro = Label::new(bb_def(), "A GUI for the STM32F746G Discovery board, made by");
+    intro.get_text_layout().vertical = VerticalAlign::Top;
+    intro.get_text_layout().wrap = true;
+
     let mut f = VerticalLayout::new(bb_screen());
-    f.add_form(button("------------"));
+    f.add_form(Box::new(intro));
     f.add_form(button("Didong"));
     f.add_form(button("Drexler"));
     f.add_form(button("Speitelsbach"));
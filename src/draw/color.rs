@@ -0,0 +1,110 @@
+/// A 32 bit RGBA color.
+///
+/// This mirrors the color type of the stm32f7 lcd module so that the forms
+/// do not depend on the board crate and can be rendered by the simulator.
+/// In memory it is laid out like the bytes of ARGB8888 with red and blue
+/// swapped, so the DMA2D can read decoded bitmaps.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl Color {
+    pub fn rgb(red: u8, green: u8, blue: u8) -> Color {
+        Color::rgba(red, green, blue, 255)
+    }
+
+    pub fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Color {
+        Color {
+            red: red,
+            green: green,
+            blue: blue,
+            alpha: alpha,
+        }
+    }
+
+    pub fn from_hex(hex: u32) -> Color {
+        assert!(hex <= 0xFFFFFF);
+        Color::rgb((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
+    }
+
+    /// Composes the color over `below` by its alpha. The integer math is the
+    /// one of the DMA2D's blender, so both give the same pixels.
+    pub fn over(&self, below: Color) -> Color {
+        let alpha = self.alpha as u32;
+        if alpha == 255 || below.alpha == 0 {
+            return *self;
+        }
+
+        // below is weighted by what shines through of it, the DMA2D rounds
+        // the product of both alphas down and subtracts it
+        let below_alpha = below.alpha as u32 - alpha * below.alpha as u32 / 255;
+        let out_alpha = alpha + below_alpha;
+        if out_alpha == 0 {
+            return Color::rgba(0, 0, 0, 0);
+        }
+        let mix = |src: u8, dst: u8| {
+            ((src as u32 * alpha + dst as u32 * below_alpha) / out_alpha) as u8
+        };
+        Color::rgba(mix(self.red, below.red),
+                    mix(self.green, below.green),
+                    mix(self.blue, below.blue),
+                    out_alpha as u8)
+    }
+
+    /// Adds the color weighted by its alpha to `below`, e.g. for glows.
+    pub fn add(&self, below: Color) -> Color {
+        let alpha = self.alpha as u32;
+        let add = |src: u8, dst: u8| {
+            let sum = src as u32 * alpha / 255 + dst as u32;
+            if sum > 255 { 255 } else { sum as u8 }
+        };
+        Color::rgba(add(self.red, below.red),
+                    add(self.green, below.green),
+                    add(self.blue, below.blue),
+                    add(255, below.alpha))
+    }
+
+    pub fn to_argb8888(&self) -> u32 {
+        (self.alpha as u32) << 24 | (self.red as u32) << 16 | (self.green as u32) << 8 |
+        (self.blue as u32)
+    }
+
+    pub fn from_argb8888(value: u32) -> Color {
+        Color::rgba((value >> 16) as u8,
+                    (value >> 8) as u8,
+                    value as u8,
+                    (value >> 24) as u8)
+    }
+
+    // Drops the alpha, the pixel is opaque.
+    pub fn to_rgb565(&self) -> u16 {
+        (self.red as u16 >> 3) << 11 | (self.green as u16 >> 2) << 5 | (self.blue as u16 >> 3)
+    }
+
+    pub fn from_rgb565(value: u16) -> Color {
+        // repeat the high bits in the low ones so that white stays white
+        let red = (value >> 11) as u8 & 0x1F;
+        let green = (value >> 5) as u8 & 0x3F;
+        let blue = value as u8 & 0x1F;
+        Color::rgb(red << 3 | red >> 2, green << 2 | green >> 4, blue << 3 | blue >> 2)
+    }
+
+    pub fn to_argb4444(&self) -> u16 {
+        (self.alpha as u16 >> 4) << 12 | (self.red as u16 >> 4) << 8 |
+        (self.green as u16 >> 4) << 4 | (self.blue as u16 >> 4)
+    }
+
+    pub fn from_argb4444(value: u16) -> Color {
+        // repeat the nibbles so that 0xF becomes 0xFF
+        let expand = |nibble: u16| ((nibble & 0xF) * 0x11) as u8;
+        Color::rgba(expand(value >> 8),
+                    expand(value >> 4),
+                    expand(value),
+                    expand(value >> 12))
+    }
+}
@@ -0,0 +1,193 @@
+//! Describes the display the forms are shown on.
+//!
+//! Layouts and views take the screen size from the current display, the
+//! framebuffer its memory layout. It is set once at startup, before the
+//! views are created.
+
+use draw::Color;
+use util::bounding_box::BoundingBox;
+
+/// The memory layout of one pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    Argb8888,
+    Rgb565,
+    Argb4444,
+    // an index into the palette, which holds 1 up to 256 colors
+    L8(&'static [Color]),
+}
+
+impl PixelFormat {
+    pub fn bytes_per_pixel(&self) -> i32 {
+        match *self {
+            PixelFormat::Argb8888 => 4,
+            PixelFormat::Rgb565 | PixelFormat::Argb4444 => 2,
+            PixelFormat::L8(_) => 1,
+        }
+    }
+
+    /// Returns the pixel value in the low bytes.
+    pub fn encode(&self, color: Color) -> u32 {
+        match *self {
+            PixelFormat::Argb8888 => color.to_argb8888(),
+            PixelFormat::Rgb565 => color.to_rgb565() as u32,
+            PixelFormat::Argb4444 => color.to_argb4444() as u32,
+            PixelFormat::L8(palette) => nearest(palette, color) as u32,
+        }
+    }
+
+    pub fn decode(&self, value: u32) -> Color {
+        match *self {
+            PixelFormat::Argb8888 => Color::from_argb8888(value),
+            PixelFormat::Rgb565 => Color::from_rgb565(value as u16),
+            PixelFormat::Argb4444 => Color::from_argb4444(value as u16),
+            PixelFormat::L8(palette) => {
+                palette
+                    .get(value as usize & 0xFF)
+                    .cloned()
+                    .unwrap_or(Color::rgba(0, 0, 0, 0))
+            }
+        }
+    }
+}
+
+// Index of the palette entry closest to the color.
+fn nearest(palette: &[Color], color: Color) -> u8 {
+    let distance = |other: &Color| {
+        let diff = |a: u8, b: u8| (a as i32 - b as i32) * (a as i32 - b as i32);
+        diff(color.red, other.red) + diff(color.green, other.green) +
+        diff(color.blue, other.blue) + diff(color.alpha, other.alpha)
+    };
+
+    let mut best = 0;
+    for (i, entry) in palette.iter().enumerate().take(256) {
+        if distance(entry) < distance(&palette[best]) {
+            best = i;
+        }
+    }
+    best as u8
+}
+
+/// The size and memory layout of a display, created with `new` so an `L8`
+/// display always has a palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayInfo {
+    width: i32,
+    height: i32,
+    // bytes from the start of one row to the next
+    stride: i32,
+    format: PixelFormat,
+}
+
+impl DisplayInfo {
+    /// Panics for an `L8` format with an empty palette, no color could be
+    /// drawn with it.
+    pub fn new(width: i32, height: i32, format: PixelFormat) -> DisplayInfo {
+        if let PixelFormat::L8(palette) = format {
+            assert!(!palette.is_empty(), "the palette of an L8 display is empty");
+        }
+
+        DisplayInfo {
+            width: width,
+            height: height,
+            stride: width * format.bytes_per_pixel(),
+            format: format,
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Bytes from the start of one row to the next.
+    pub fn stride(&self) -> i32 {
+        self.stride
+    }
+
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    pub fn get_bounding_box(&self) -> BoundingBox {
+        BoundingBox {
+            x: 0,
+            y: 0,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    // Bytes of one layer.
+    pub fn layer_size(&self) -> u32 {
+        (self.stride * self.height) as u32
+    }
+}
+
+/// The 4.3" panel of the STM32F746 Discovery as the lcd module sets it up.
+pub const DISCOVERY_F746: DisplayInfo = DisplayInfo {
+    width: 480,
+    height: 272,
+    stride: 480 * 2,
+    format: PixelFormat::Argb4444,
+};
+
+/// The 4" panel of the STM32F769 Discovery.
+pub const DISCOVERY_F769: DisplayInfo = DisplayInfo {
+    width: 800,
+    height: 480,
+    stride: 800 * 4,
+    format: PixelFormat::Argb8888,
+};
+
+// The board runs the application in one thread without interrupt handlers
+// that touch the display, and the simulator sets it before it starts, so
+// the reads in `current` never race with the write in `set_current`.
+static mut CURRENT: DisplayInfo = DISCOVERY_F746;
+
+/// The display the application runs on, the F746 panel if not set.
+pub fn current() -> DisplayInfo {
+    // a copy, see `CURRENT` for why the read is safe
+    unsafe { CURRENT }
+}
+
+/// Sets the display, has to be called before any view is created and from
+/// the thread that runs the application.
+pub fn set_current(info: DisplayInfo) {
+    // nothing reads `CURRENT` meanwhile, see there
+    unsafe {
+        CURRENT = info;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_and_decode() {
+        for format in &[PixelFormat::Argb8888, PixelFormat::Rgb565, PixelFormat::Argb4444] {
+            let white = Color::rgb(255, 255, 255);
+            assert_eq!(format.decode(format.encode(white)), white);
+        }
+        let gray = Color::rgb(0x40, 0x41, 0x42);
+        assert_eq!(PixelFormat::Rgb565.decode(PixelFormat::Rgb565.encode(gray)),
+                   Color::rgb(0x42, 0x41, 0x42));
+
+        static PALETTE: [Color; 3] = [Color { red: 0, green: 0, blue: 0, alpha: 255 },
+                                      Color { red: 255, green: 0, blue: 0, alpha: 255 },
+                                      Color { red: 255, green: 255, blue: 255, alpha: 255 }];
+        let l8 = PixelFormat::L8(&PALETTE);
+        assert_eq!(l8.encode(Color::rgb(200, 30, 20)), 1);
+        assert_eq!(l8.decode(2), Color::rgb(255, 255, 255));
+    }
+
+    #[test]
+    #[should_panic]
+    fn l8_display_needs_a_palette() {
+        DisplayInfo::new(4, 4, PixelFormat::L8(&[]));
+    }
+}
@@ -0,0 +1,163 @@
+//! The Chrom-ART accelerator (DMA2D) of the STM32F7, it fills, copies and
+//! blends rectangles of pixels in memory and converts them between pixel
+//! formats.
+//!
+//! Each transfer is waited for before returning, so the CPU never touches
+//! pixels the DMA2D is still working on. The functions return false for
+//! what the peripheral cannot do, e.g. L8 pixels that would need the CLUT
+//! to be loaded, the caller then draws the pixels in software.
+
+use core::ptr;
+
+use draw::PixelFormat;
+use draw::Surface;
+use util::bounding_box::BoundingBox;
+
+const DMA2D_BASE: u32 = 0x4002_B000;
+
+// register offsets
+const CR: u32 = 0x00;
+const ISR: u32 = 0x04;
+const IFCR: u32 = 0x08;
+const FGMAR: u32 = 0x0C;
+const FGOR: u32 = 0x10;
+const BGMAR: u32 = 0x14;
+const BGOR: u32 = 0x18;
+const FGPFCCR: u32 = 0x1C;
+const BGPFCCR: u32 = 0x24;
+const OPFCCR: u32 = 0x34;
+const OCOLR: u32 = 0x38;
+const OMAR: u32 = 0x3C;
+const OOR: u32 = 0x40;
+const NLR: u32 = 0x44;
+
+const CR_START: u32 = 1 << 0;
+const CR_MODE_M2M_PFC: u32 = 1 << 16;
+const CR_MODE_M2M_BLEND: u32 = 2 << 16;
+const CR_MODE_R2M: u32 = 3 << 16;
+
+// transfer error, transfer complete and configuration error
+const ISR_TEIF: u32 = 1 << 0;
+const ISR_TCIF: u32 = 1 << 1;
+const ISR_CEIF: u32 = 1 << 5;
+
+// swaps red and blue of the foreground pixels
+const PFCCR_RBS: u32 = 1 << 21;
+
+const RCC_AHB1ENR: u32 = 0x4002_3830;
+const RCC_AHB1ENR_DMA2DEN: u32 = 1 << 23;
+
+unsafe fn write(register: u32, value: u32) {
+    ptr::write_volatile((DMA2D_BASE + register) as *mut u32, value);
+}
+
+unsafe fn read(register: u32) -> u32 {
+    ptr::read_volatile((DMA2D_BASE + register) as *const u32)
+}
+
+/// Enables the clock of the DMA2D, it does not hurt to call it again.
+pub fn init() {
+    let ahb1enr = RCC_AHB1ENR as *mut u32;
+    unsafe {
+        ptr::write_volatile(ahb1enr, ptr::read_volatile(ahb1enr) | RCC_AHB1ENR_DMA2DEN);
+    }
+}
+
+// The color mode field of the pixel format converter registers.
+fn color_mode(format: PixelFormat) -> Option<u32> {
+    match format {
+        PixelFormat::Argb8888 => Some(0),
+        PixelFormat::Rgb565 => Some(2),
+        PixelFormat::Argb4444 => Some(4),
+        PixelFormat::L8(_) => None,
+    }
+}
+
+// The address of pixel `x`, `y` and the number of pixels to skip after each
+// row of `width` pixels.
+fn position(surface: &Surface, x: i32, y: i32, width: i32) -> (u32, u32) {
+    let bytes = surface.format.bytes_per_pixel();
+    let addr = surface.addr + (y * surface.stride + x * bytes) as usize;
+    (addr as u32, (surface.stride / bytes - width) as u32)
+}
+
+// Starts the transfer of `area` that was set up and waits until it is done.
+unsafe fn run(mode: u32, area: &BoundingBox) -> bool {
+    write(NLR, (area.width as u32) << 16 | area.height as u32);
+    write(CR, mode | CR_START);
+    while read(CR) & CR_START != 0 {}
+
+    let status = read(ISR);
+    write(IFCR, ISR_TEIF | ISR_TCIF | ISR_CEIF);
+    status & (ISR_TEIF | ISR_CEIF) == 0
+}
+
+// Sets up the foreground to be read from `source` starting at `x`, `y`.
+unsafe fn set_foreground(source: &Surface, x: i32, y: i32, area: &BoundingBox) -> bool {
+    let mode = match color_mode(source.format) {
+        Some(mode) => mode,
+        None => return false,
+    };
+    let (addr, offset) = position(source, x, y, area.width);
+
+    write(FGMAR, addr);
+    write(FGOR, offset);
+    write(FGPFCCR,
+          if source.swap_red_blue {
+              mode | PFCCR_RBS
+          } else {
+              mode
+          });
+    true
+}
+
+// Sets up `area` of `target` as output.
+unsafe fn set_output(target: &Surface, area: &BoundingBox) -> bool {
+    let mode = match color_mode(target.format) {
+        Some(mode) => mode,
+        None => return false,
+    };
+    let (addr, offset) = position(target, area.x, area.y, area.width);
+
+    write(OPFCCR, mode);
+    write(OMAR, addr);
+    write(OOR, offset);
+    true
+}
+
+/// Fills `area` of `target` with `value`, a pixel in the target's format.
+pub fn fill(target: &Surface, area: &BoundingBox, value: u32) -> bool {
+    unsafe {
+        if !set_output(target, area) {
+            return false;
+        }
+        write(OCOLR, value);
+        run(CR_MODE_R2M, area)
+    }
+}
+
+/// Copies the pixels from `x`, `y` of `source` to `area` of `target` and
+/// converts them to the target's format.
+pub fn copy(source: &Surface, x: i32, y: i32, target: &Surface, area: &BoundingBox) -> bool {
+    unsafe {
+        if !set_foreground(source, x, y, area) || !set_output(target, area) {
+            return false;
+        }
+        run(CR_MODE_M2M_PFC, area)
+    }
+}
+
+/// Composes the pixels from `x`, `y` of `source` over `area` of `target` by
+/// their alpha.
+pub fn blend(source: &Surface, x: i32, y: i32, target: &Surface, area: &BoundingBox) -> bool {
+    unsafe {
+        if !set_foreground(source, x, y, area) || !set_output(target, area) {
+            return false;
+        }
+        // the background is the target itself
+        write(BGMAR, read(OMAR));
+        write(BGOR, read(OOR));
+        write(BGPFCCR, read(OPFCCR));
+        run(CR_MODE_M2M_BLEND, area)
+    }
+}
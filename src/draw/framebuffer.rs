@@ -0,0 +1,199 @@
+use core::cmp::max;
+use core::cmp::min;
+use core::ptr;
+
+use draw::BlendMode;
+use draw::Color;
+use draw::DrawState;
+use draw::DisplayInfo;
+use draw::DrawTarget;
+use draw::Surface;
+use draw::dma2d;
+use image::Bitmap;
+use util::bounding_box::BoundingBox;
+
+// LTDC current display status register and its vertical sync flag.
+const LTDC_CDSR: u32 = 0x4001_6848;
+const LTDC_CDSR_VSYNCS: u32 = 1 << 2;
+
+// The frame buffer address, length and line number registers of layer 1,
+// the ones of layer 2 follow at the offset.
+const LTDC_L1CFBAR: u32 = 0x4001_68AC;
+const LTDC_L1CFBLR: u32 = 0x4001_68B0;
+const LTDC_L1CFBLNR: u32 = 0x4001_68B4;
+const LTDC_LAYER_OFFSET: u32 = 0x80;
+
+// The address, pitch in bytes and number of lines of the memory the LTDC
+// shows on layer 1 (0) or 2 (1), as the lcd module set it up.
+fn ltdc_layer(layer: u32) -> (u32, i32, i32) {
+    let offset = layer * LTDC_LAYER_OFFSET;
+    unsafe {
+        let addr = ptr::read_volatile((LTDC_L1CFBAR + offset) as *const u32);
+        let length = ptr::read_volatile((LTDC_L1CFBLR + offset) as *const u32);
+        let lines = ptr::read_volatile((LTDC_L1CFBLNR + offset) as *const u32);
+        (addr, (length >> 16 & 0x1FFF) as i32, (lines & 0x7FF) as i32)
+    }
+}
+
+/// Layer 1 of the LTDC, at the address the LTDC reads it from. The LTDC has
+/// to be set up with the size and pixel format of the display. Layer 2 is
+/// left to the stm32f7 stdout for debug output.
+///
+/// Rectangles, copies and bitmaps are drawn by the DMA2D if it supports the
+/// pixel formats and blend mode, everything else in software.
+pub struct Framebuffer {
+    info: DisplayInfo,
+    addr: u32,
+    state: DrawState,
+}
+
+impl Framebuffer {
+    pub fn new(info: DisplayInfo) -> Framebuffer {
+        let (addr, pitch, lines) = ltdc_layer(0);
+        assert!(pitch == info.stride() && lines == info.height(),
+                "the LTDC layer does not match the display");
+
+        dma2d::init();
+        Framebuffer {
+            info: info,
+            addr: addr,
+            state: DrawState::new(),
+        }
+    }
+
+    /// A buffer of the size of the display in SDRAM behind the memory of
+    /// both LTDC layers. It is not shown and is meant as back buffer.
+    pub fn offscreen(info: DisplayInfo) -> Framebuffer {
+        let end = |(addr, pitch, lines): (u32, i32, i32)| addr + (pitch * lines) as u32;
+        let addr = max(end(ltdc_layer(0)), end(ltdc_layer(1)));
+
+        dma2d::init();
+        Framebuffer {
+            info: info,
+            addr: addr,
+            state: DrawState::new(),
+        }
+    }
+
+    fn pixel_addr(&self, x: i32, y: i32) -> u32 {
+        self.addr + (y * self.info.stride() + x * self.info.format().bytes_per_pixel()) as u32
+    }
+}
+
+impl DrawTarget for Framebuffer {
+    fn width(&self) -> i32 {
+        self.info.width()
+    }
+
+    fn height(&self) -> i32 {
+        self.info.height()
+    }
+
+    fn state(&mut self) -> &mut DrawState {
+        &mut self.state
+    }
+
+    fn put_pixel(&mut self, x: i32, y: i32, color: Color) -> () {
+        let addr = self.pixel_addr(x, y);
+        let value = self.info.format().encode(color);
+
+        unsafe {
+            match self.info.format().bytes_per_pixel() {
+                4 => ptr::write_volatile(addr as *mut u32, value),
+                2 => ptr::write_volatile(addr as *mut u16, value as u16),
+                _ => ptr::write_volatile(addr as *mut u8, value as u8),
+            }
+        }
+    }
+
+    fn get_pixel(&self, x: i32, y: i32) -> Color {
+        let addr = self.pixel_addr(x, y);
+        let value = unsafe {
+            match self.info.format().bytes_per_pixel() {
+                4 => ptr::read_volatile(addr as *const u32),
+                2 => ptr::read_volatile(addr as *const u16) as u32,
+                _ => ptr::read_volatile(addr as *const u8) as u32,
+            }
+        };
+
+        self.info.format().decode(value)
+    }
+
+    fn pixel_memory(&self) -> Option<Surface> {
+        Some(Surface {
+            addr: self.addr as usize,
+            stride: self.info.stride(),
+            format: self.info.format(),
+            swap_red_blue: false,
+        })
+    }
+
+    fn fill_rect(&mut self, area: &BoundingBox, color: Color) -> () {
+        // the DMA2D can only overwrite the pixels
+        let blend_mode = self.state.get_blend_mode();
+        if blend_mode == BlendMode::Replace ||
+           (blend_mode == BlendMode::SourceOver && color.alpha == 255) {
+            let visible = match self.visible_area(area) {
+                Some(visible) => visible,
+                None => return,
+            };
+            let target = self.pixel_memory().unwrap();
+            if dma2d::fill(&target, &visible, self.info.format().encode(color)) {
+                return;
+            }
+        }
+
+        self.fill_rect_software(area, color);
+    }
+
+    fn draw_bitmap(&mut self, x: i32, y: i32, bitmap: &Bitmap) -> () {
+        let area = BoundingBox {
+            x: x,
+            y: y,
+            width: bitmap.width(),
+            height: bitmap.height(),
+        };
+        let visible = match self.visible_area(&area) {
+            Some(visible) => visible,
+            None => return,
+        };
+        let source = bitmap.surface();
+        let target = self.pixel_memory().unwrap();
+
+        let (source_x, source_y) = (visible.x - x, visible.y - y);
+        let done = match self.state.get_blend_mode() {
+            BlendMode::Replace => dma2d::copy(&source, source_x, source_y, &target, &visible),
+            BlendMode::SourceOver => dma2d::blend(&source, source_x, source_y, &target, &visible),
+            BlendMode::Additive => false,
+        };
+        if !done {
+            self.draw_bitmap_software(x, y, bitmap);
+        }
+    }
+
+    fn copy_area(&mut self, source: &DrawTarget, area: &BoundingBox) -> () {
+        if let Some(memory) = source.pixel_memory() {
+            let common = BoundingBox {
+                x: 0,
+                y: 0,
+                width: min(self.info.width(), source.width()),
+                height: min(self.info.height(), source.height()),
+            };
+            let area = match common.intersection(area) {
+                Some(area) => area,
+                None => return,
+            };
+            let target = self.pixel_memory().unwrap();
+            if dma2d::copy(&memory, area.x, area.y, &target, &area) {
+                return;
+            }
+        }
+
+        self.copy_area_software(source, area);
+    }
+
+    fn wait_for_vsync(&mut self) -> () {
+        let cdsr = LTDC_CDSR as *const u32;
+        while unsafe { ptr::read_volatile(cdsr) } & LTDC_CDSR_VSYNCS == 0 {}
+    }
+}
@@ -0,0 +1,216 @@
+#![allow(dead_code)]
+
+mod color;
+pub mod display;
+#[cfg(feature = "board")]
+mod dma2d;
+#[cfg(feature = "board")]
+mod framebuffer;
+mod primitives;
+mod state;
+
+pub use self::color::Color;
+pub use self::display::DisplayInfo;
+pub use self::display::PixelFormat;
+pub use self::state::BlendMode;
+pub use self::state::DrawState;
+#[cfg(feature = "board")]
+pub use self::framebuffer::Framebuffer;
+pub use self::primitives::*;
+
+use core::cmp::max;
+use core::cmp::min;
+
+use image::Bitmap;
+use util::bounding_box::BoundingBox;
+
+/// Pixels in memory, for targets and bitmaps the DMA2D can read from or
+/// write to.
+#[derive(Debug, Clone, Copy)]
+pub struct Surface {
+    pub addr: usize,
+    // bytes from the start of one row to the next
+    pub stride: i32,
+    pub format: PixelFormat,
+    // ARGB8888 pixels stored in the order of `Color`, red first
+    pub swap_red_blue: bool,
+}
+
+// The new color of a pixel, None if it stays as it is. `below` is only read
+// when the blend mode needs it.
+fn blend<F: Fn() -> Color>(color: Color, blend_mode: BlendMode, below: F) -> Option<Color> {
+    match blend_mode {
+        BlendMode::Replace => Some(color),
+        BlendMode::SourceOver if color.alpha == 255 => Some(color),
+        BlendMode::SourceOver if color.alpha == 0 => None,
+        BlendMode::SourceOver => Some(color.over(below())),
+        BlendMode::Additive => Some(color.add(below())),
+    }
+}
+
+/// A surface the forms render into.
+///
+/// Implementors only have to provide the size and a raw pixel write, all
+/// other primitives are built on top of `draw_pixel`. This way the same form
+/// tree can be drawn to the LTDC layers, an in-memory buffer or a test harness.
+///
+/// Everything drawn through `draw_pixel` is clipped to the current clip
+/// rectangle of the target's `DrawState` and blended with its blend mode.
+/// Transparent pixels are the background, the LTDC shows the layers below
+/// through them.
+///
+/// Rectangles and bitmaps are drawn as a whole by `fill_rect`, `draw_bitmap`
+/// and `copy_area`. The framebuffer hands them to the DMA2D, the software
+/// versions they fall back to write the same pixels without a bounds check
+/// for each of them.
+pub trait DrawTarget {
+    fn width(&self) -> i32;
+    fn height(&self) -> i32;
+    fn state(&mut self) -> &mut DrawState;
+
+    // Writes a pixel without any bounds check, use `draw_pixel` instead.
+    fn put_pixel(&mut self, x: i32, y: i32, color: Color) -> ();
+    // Reads a pixel without any bounds check.
+    fn get_pixel(&self, x: i32, y: i32) -> Color;
+
+    fn draw_pixel(&mut self, x: i32, y: i32, color: Color) -> bool {
+        let blend_mode = self.state().get_blend_mode();
+        self.blend_pixel(x, y, color, blend_mode)
+    }
+
+    fn blend_pixel(&mut self, x: i32, y: i32, color: Color, blend_mode: BlendMode) -> bool {
+        if x < 0 || y < 0 || x >= self.width() || y >= self.height() {
+            return false;
+        }
+        if !self.state().is_visible(x, y) {
+            return false;
+        }
+
+        let blended = blend(color, blend_mode, || self.get_pixel(x, y));
+        if let Some(color) = blended {
+            self.put_pixel(x, y, color);
+        }
+
+        true
+    }
+
+    // Returns the previous mode, so it can be restored.
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) -> BlendMode {
+        self.state().set_blend_mode(blend_mode)
+    }
+
+    fn push_clip(&mut self, area: &BoundingBox) -> () {
+        self.state().push_clip(area);
+    }
+
+    fn pop_clip(&mut self) -> () {
+        self.state().pop_clip();
+    }
+
+    // The part of `area` on the target and inside the clip rectangle.
+    fn visible_area(&mut self, area: &BoundingBox) -> Option<BoundingBox> {
+        let screen = BoundingBox {
+            x: 0,
+            y: 0,
+            width: self.width(),
+            height: self.height(),
+        };
+        let area = match screen.intersection(area) {
+            Some(area) => area,
+            None => return None,
+        };
+        match self.state().get_clip() {
+            Some(clip) => clip.intersection(&area),
+            None => Some(area),
+        }
+    }
+
+    // Where the pixels are in memory, for targets the DMA2D can access.
+    fn pixel_memory(&self) -> Option<Surface> {
+        None
+    }
+
+    /// Fills `area` with `color`, clipped and blended like `draw_pixel`.
+    fn fill_rect(&mut self, area: &BoundingBox, color: Color) -> () {
+        self.fill_rect_software(area, color);
+    }
+
+    fn fill_rect_software(&mut self, area: &BoundingBox, color: Color) -> () {
+        let area = match self.visible_area(area) {
+            Some(area) => area,
+            None => return,
+        };
+        let blend_mode = self.state().get_blend_mode();
+
+        for y in area.y..area.y + area.height {
+            for x in area.x..area.x + area.width {
+                let blended = blend(color, blend_mode, || self.get_pixel(x, y));
+                if let Some(color) = blended {
+                    self.put_pixel(x, y, color);
+                }
+            }
+        }
+    }
+
+    /// Draws the bitmap in its original size with its top left corner at
+    /// `x`, `y`, clipped and blended like `draw_pixel`.
+    fn draw_bitmap(&mut self, x: i32, y: i32, bitmap: &Bitmap) -> () {
+        self.draw_bitmap_software(x, y, bitmap);
+    }
+
+    fn draw_bitmap_software(&mut self, x: i32, y: i32, bitmap: &Bitmap) -> () {
+        let area = BoundingBox {
+            x: x,
+            y: y,
+            width: bitmap.width(),
+            height: bitmap.height(),
+        };
+        let area = match self.visible_area(&area) {
+            Some(area) => area,
+            None => return,
+        };
+        let blend_mode = self.state().get_blend_mode();
+
+        for target_y in area.y..area.y + area.height {
+            for target_x in area.x..area.x + area.width {
+                let color = bitmap.get_pixel(target_x - x, target_y - y);
+                let blended = blend(color, blend_mode, || self.get_pixel(target_x, target_y));
+                if let Some(color) = blended {
+                    self.put_pixel(target_x, target_y, color);
+                }
+            }
+        }
+    }
+
+    // Resets the area to the transparent background.
+    fn clear_area(&mut self, area: &BoundingBox) -> () {
+        let blend_mode = self.set_blend_mode(BlendMode::Replace);
+        self.fill_rect(area, Color::rgba(0, 0, 0, 0));
+        self.set_blend_mode(blend_mode);
+    }
+
+    // Replaces the area with the content of `source`, e.g. to show a frame
+    // that was composed in a back buffer. The clip rectangle is ignored.
+    fn copy_area(&mut self, source: &DrawTarget, area: &BoundingBox) -> () {
+        self.copy_area_software(source, area);
+    }
+
+    fn copy_area_software(&mut self, source: &DrawTarget, area: &BoundingBox) -> () {
+        let width = min(self.width(), source.width());
+        let height = min(self.height(), source.height());
+
+        for y in max(area.y, 0)..min(area.y + area.height, height) {
+            for x in max(area.x, 0)..min(area.x + area.width, width) {
+                self.put_pixel(x, y, source.get_pixel(x, y));
+            }
+        }
+    }
+
+    // Blocks until the display is in its vertical blanking period, targets
+    // that are not shown directly return immediately.
+    fn wait_for_vsync(&mut self) -> () {}
+}
+
+pub fn draw_pixel(target: &mut DrawTarget, x: i32, y: i32, color: Color) -> bool {
+    target.draw_pixel(x, y, color)
+}
@@ -0,0 +1,407 @@
+//! Shapes built on top of `DrawTarget::draw_pixel` and `fill_rect`, so they
+//! are clipped like every other pixel.
+//!
+//! Rectangles include their last row and column like the bounding boxes of
+//! the forms are drawn, angles are in degrees clockwise from the x axis
+//! because y points down.
+
+use collections::Vec;
+use core::cmp::max;
+use core::cmp::min;
+use core::mem;
+
+use draw::Color;
+use draw::DrawTarget;
+use util::bounding_box::BoundingBox;
+use util::math::atan2_deg;
+use util::math::isqrt;
+
+// Draws `color` with `coverage` (0 to 255) of its alpha, for the edges of
+// anti-aliased shapes. The blend mode of the target composes it.
+fn draw_coverage(target: &mut DrawTarget, x: i32, y: i32, color: Color, coverage: u8) {
+    if coverage == 0 {
+        return;
+    }
+
+    let alpha = (color.alpha as u32 * coverage as u32 / 255) as u8;
+    target.draw_pixel(x, y, Color { alpha: alpha, ..color });
+}
+
+/// A one pixel line from (x1, y1) to (x2, y2), both ends included.
+pub fn draw_line(target: &mut DrawTarget, x1: i32, y1: i32, x2: i32, y2: i32, color: Color) {
+    // Bresenham
+    let dx = (x2 - x1).abs();
+    let dy = -(y2 - y1).abs();
+    let step_x = if x1 < x2 { 1 } else { -1 };
+    let step_y = if y1 < y2 { 1 } else { -1 };
+
+    let (mut x, mut y) = (x1, y1);
+    let mut error = dx + dy;
+    loop {
+        target.draw_pixel(x, y, color);
+        if x == x2 && y == y2 {
+            break;
+        }
+
+        let error2 = 2 * error;
+        if error2 >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if error2 <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+}
+
+/// An anti-aliased one pixel line, the pixels next to the ideal line are
+/// blended by their distance to it.
+pub fn draw_line_aa(target: &mut DrawTarget,
+                    x1: i32,
+                    y1: i32,
+                    x2: i32,
+                    y2: i32,
+                    color: Color) {
+    // Wu, stepping along the longer axis with a 16.16 fixed point position
+    // on the other one
+    let steep = (y2 - y1).abs() > (x2 - x1).abs();
+    let (mut x1, mut y1, mut x2, mut y2) = if steep {
+        (y1, x1, y2, x2)
+    } else {
+        (x1, y1, x2, y2)
+    };
+    if x1 > x2 {
+        mem::swap(&mut x1, &mut x2);
+        mem::swap(&mut y1, &mut y2);
+    }
+
+    let dx = x2 - x1;
+    let gradient = if dx == 0 { 0 } else { ((y2 - y1) << 16) / dx };
+    let mut y = y1 << 16;
+    for x in x1..x2 + 1 {
+        let (whole, fraction) = (y >> 16, ((y >> 8) & 0xFF) as u8);
+        if steep {
+            draw_coverage(target, whole, x, color, 255 - fraction);
+            draw_coverage(target, whole + 1, x, color, fraction);
+        } else {
+            draw_coverage(target, x, whole, color, 255 - fraction);
+            draw_coverage(target, x, whole + 1, color, fraction);
+        }
+        y += gradient;
+    }
+}
+
+/// A line of `width` pixels with square ends, centered on the line from
+/// (x1, y1) to (x2, y2).
+pub fn draw_thick_line(target: &mut DrawTarget,
+                       x1: i32,
+                       y1: i32,
+                       x2: i32,
+                       y2: i32,
+                       width: i32,
+                       color: Color) {
+    let (dx, dy) = (x2 - x1, y2 - y1);
+    let length = isqrt((dx * dx + dy * dy) as u32) as i32;
+    if width <= 1 || length == 0 {
+        draw_line(target, x1, y1, x2, y2, color);
+        return;
+    }
+
+    // the normal with the length of the width in 1/16 pixels, split into
+    // the parts on either side of the line
+    let normal_x = round_16(-dy * (width - 1) * 16 / length);
+    let normal_y = round_16(dx * (width - 1) * 16 / length);
+    let (offset_x, offset_y) = (normal_x / 2, normal_y / 2);
+    let (other_x, other_y) = (offset_x - normal_x, offset_y - normal_y);
+
+    fill_polygon(target,
+                 &[(x1 + offset_x, y1 + offset_y),
+                   (x2 + offset_x, y2 + offset_y),
+                   (x2 + other_x, y2 + other_y),
+                   (x1 + other_x, y1 + other_y)],
+                 color);
+}
+
+fn round_16(value: i32) -> i32 {
+    if value >= 0 {
+        (value + 8) / 16
+    } else {
+        -((8 - value) / 16)
+    }
+}
+
+pub fn draw_rectangle(target: &mut DrawTarget,
+                      x: i32,
+                      y: i32,
+                      width: i32,
+                      height: i32,
+                      color: Color)
+                      -> bool {
+    for i in x..x + width + 1 {
+        target.draw_pixel(i, y, color);
+        target.draw_pixel(i, y + height, color);
+    }
+    for i in y..y + height + 1 {
+        target.draw_pixel(x, i, color);
+        target.draw_pixel(x + width, i, color);
+    }
+
+    true
+}
+
+/// A rectangle outline `thickness` pixels wide towards the inside.
+pub fn draw_thick_rectangle(target: &mut DrawTarget,
+                            x: i32,
+                            y: i32,
+                            width: i32,
+                            height: i32,
+                            thickness: i32,
+                            color: Color) {
+    if thickness * 2 > min(width, height) {
+        fill_rectangle(target, x, y, width, height, color);
+        return;
+    }
+
+    fill_rectangle(target, x, y, width, thickness - 1, color);
+    fill_rectangle(target, x, y + height - thickness + 1, width, thickness - 1, color);
+    fill_rectangle(target, x, y, thickness - 1, height, color);
+    fill_rectangle(target, x + width - thickness + 1, y, thickness - 1, height, color);
+}
+
+pub fn fill_rectangle(target: &mut DrawTarget,
+                      x: i32,
+                      y: i32,
+                      width: i32,
+                      height: i32,
+                      color: Color)
+                      -> bool {
+    target.fill_rect(&BoundingBox {
+                         x: x,
+                         y: y,
+                         width: width + 1,
+                         height: height + 1,
+                     },
+                     color);
+
+    true
+}
+
+// Half the width of an ellipse with the radii rx and ry at row dy from its
+// center.
+fn ellipse_span(rx: i32, ry: i32, dy: i32) -> i32 {
+    if ry <= 0 {
+        return rx;
+    }
+    if dy.abs() > ry {
+        return -1;
+    }
+
+    // the radii are taken as r + 1/2, (r + 1/2)^2 is about r^2 + r, so
+    // the outline does not get single pixel tips
+    let rx2 = (rx * rx + rx) as u64;
+    let ry2 = (ry * ry + ry) as u64;
+    let rest = ry2 - (dy * dy) as u64;
+    isqrt((rx2 * rest / ry2) as u32) as i32
+}
+
+// One quarter of an ellipse outline, `sign_x` and `sign_y` select it.
+fn draw_quadrant(target: &mut DrawTarget,
+                 cx: i32,
+                 cy: i32,
+                 rx: i32,
+                 ry: i32,
+                 sign_x: i32,
+                 sign_y: i32,
+                 color: Color) {
+    for dy in 0..ry + 1 {
+        let outer = ellipse_span(rx, ry, dy);
+        // the row closer to the edge decides where the gap to close starts
+        let inner = if dy < ry {
+            ellipse_span(rx, ry, dy + 1) + 1
+        } else {
+            0
+        };
+        for dx in min(inner, outer)..outer + 1 {
+            target.draw_pixel(cx + sign_x * dx, cy + sign_y * dy, color);
+        }
+    }
+}
+
+pub fn draw_ellipse(target: &mut DrawTarget, cx: i32, cy: i32, rx: i32, ry: i32, color: Color) {
+    for &(sign_x, sign_y) in [(1, 1), (-1, 1), (1, -1), (-1, -1)].iter() {
+        draw_quadrant(target, cx, cy, rx, ry, sign_x, sign_y, color);
+    }
+}
+
+pub fn fill_ellipse(target: &mut DrawTarget, cx: i32, cy: i32, rx: i32, ry: i32, color: Color) {
+    for dy in -ry..ry + 1 {
+        let span = ellipse_span(rx, ry, dy);
+        for x in cx - span..cx + span + 1 {
+            target.draw_pixel(x, cy + dy, color);
+        }
+    }
+}
+
+pub fn draw_circle(target: &mut DrawTarget, cx: i32, cy: i32, radius: i32, color: Color) {
+    draw_ellipse(target, cx, cy, radius, radius, color);
+}
+
+pub fn fill_circle(target: &mut DrawTarget, cx: i32, cy: i32, radius: i32, color: Color) {
+    fill_ellipse(target, cx, cy, radius, radius, color);
+}
+
+/// An anti-aliased circle outline.
+pub fn draw_circle_aa(target: &mut DrawTarget, cx: i32, cy: i32, radius: i32, color: Color) {
+    // Wu, each octant is stepped along the axis it changes slower on, the
+    // position on the other axis has 4 fractional bits
+    let mut x = 0;
+    loop {
+        let rest = (radius * radius - x * x) as u32;
+        let y = isqrt(rest << 8) as i32;
+        let (whole, fraction) = (y >> 4, ((y & 0xF) * 17) as u8);
+        if x > whole {
+            break;
+        }
+
+        for &(sign_x, sign_y) in [(1, 1), (-1, 1), (1, -1), (-1, -1)].iter() {
+            let (px, py) = (sign_x * x, sign_y * whole);
+            let next = sign_y * (whole + 1);
+            draw_coverage(target, cx + px, cy + py, color, 255 - fraction);
+            draw_coverage(target, cx + px, cy + next, color, fraction);
+            draw_coverage(target, cx + py, cy + px, color, 255 - fraction);
+            draw_coverage(target, cx + next, cy + px, color, fraction);
+        }
+        x += 1;
+    }
+}
+
+/// The part of a ring of `width` pixels inside the circle of `radius` from
+/// `start` to `end` degrees.
+pub fn draw_arc(target: &mut DrawTarget,
+                cx: i32,
+                cy: i32,
+                radius: i32,
+                start: i32,
+                end: i32,
+                width: i32,
+                color: Color) {
+    let start = (start % 360 + 360) % 360;
+    let end = (end % 360 + 360) % 360;
+    let inner = max(radius - width, 0);
+    // the radius is rounded, so the outline matches `draw_circle`
+    let (outer2, inner2) = (radius * radius + radius, inner * inner + inner);
+
+    for dy in -radius..radius + 1 {
+        for dx in -radius..radius + 1 {
+            let distance2 = dx * dx + dy * dy;
+            if distance2 > outer2 || (inner > 0 && distance2 <= inner2) {
+                continue;
+            }
+
+            let angle = (atan2_deg(dy, dx) + 360) % 360;
+            let inside = if start <= end {
+                angle >= start && angle <= end
+            } else {
+                angle >= start || angle <= end
+            };
+            if inside {
+                target.draw_pixel(cx + dx, cy + dy, color);
+            }
+        }
+    }
+}
+
+pub fn draw_rounded_rectangle(target: &mut DrawTarget,
+                              x: i32,
+                              y: i32,
+                              width: i32,
+                              height: i32,
+                              radius: i32,
+                              color: Color) {
+    let radius = max(0, min(radius, min(width, height) / 2));
+    let (right, bottom) = (x + width, y + height);
+
+    for i in x + radius..right - radius + 1 {
+        target.draw_pixel(i, y, color);
+        target.draw_pixel(i, bottom, color);
+    }
+    for i in y + radius..bottom - radius + 1 {
+        target.draw_pixel(x, i, color);
+        target.draw_pixel(right, i, color);
+    }
+
+    draw_quadrant(target, x + radius, y + radius, radius, radius, -1, -1, color);
+    draw_quadrant(target, right - radius, y + radius, radius, radius, 1, -1, color);
+    draw_quadrant(target, x + radius, bottom - radius, radius, radius, -1, 1, color);
+    draw_quadrant(target, right - radius, bottom - radius, radius, radius, 1, 1, color);
+}
+
+pub fn fill_rounded_rectangle(target: &mut DrawTarget,
+                              x: i32,
+                              y: i32,
+                              width: i32,
+                              height: i32,
+                              radius: i32,
+                              color: Color) {
+    let radius = max(0, min(radius, min(width, height) / 2));
+
+    for row in y..y + height + 1 {
+        // distance into the corner rows
+        let dy = if row < y + radius {
+            y + radius - row
+        } else if row > y + height - radius {
+            row - (y + height - radius)
+        } else {
+            0
+        };
+        let inset = radius - ellipse_span(radius, radius, dy);
+        for column in x + inset..x + width - inset + 1 {
+            target.draw_pixel(column, row, color);
+        }
+    }
+}
+
+/// Connects the points and the last point with the first one.
+pub fn draw_polygon(target: &mut DrawTarget, points: &[(i32, i32)], color: Color) {
+    for (i, &(x1, y1)) in points.iter().enumerate() {
+        let (x2, y2) = points[(i + 1) % points.len()];
+        draw_line(target, x1, y1, x2, y2, color);
+    }
+}
+
+/// Fills the polygon with the even-odd rule, the points do not have to be
+/// convex.
+pub fn fill_polygon(target: &mut DrawTarget, points: &[(i32, i32)], color: Color) {
+    if points.is_empty() {
+        return;
+    }
+
+    let top = points.iter().map(|p| p.1).min().unwrap();
+    let bottom = points.iter().map(|p| p.1).max().unwrap();
+    let mut crossings = Vec::new();
+
+    for y in top..bottom + 1 {
+        crossings.clear();
+        for (i, &(x1, y1)) in points.iter().enumerate() {
+            let (x2, y2) = points[(i + 1) % points.len()];
+            // each edge covers its upper end but not its lower one, so
+            // shared corners are not counted twice
+            if (y1 <= y && y < y2) || (y2 <= y && y < y1) {
+                crossings.push(x1 + (y - y1) * (x2 - x1) / (y2 - y1));
+            }
+        }
+        crossings.sort();
+
+        for pair in crossings.chunks(2) {
+            if pair.len() == 2 {
+                for x in pair[0]..pair[1] + 1 {
+                    target.draw_pixel(x, y, color);
+                }
+            }
+        }
+    }
+
+    // the bottom row is only covered by the outline
+    draw_polygon(target, points, color);
+}
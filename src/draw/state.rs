@@ -0,0 +1,76 @@
+use collections::Vec;
+
+use util::bounding_box::BoundingBox;
+
+/// How a drawn pixel is combined with the pixel already on the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    // the color is composed over the pixel by its alpha
+    SourceOver,
+    // the pixel is overwritten including its alpha, e.g. to erase
+    Replace,
+    // the color weighted by its alpha is added to the pixel
+    Additive,
+}
+
+/// The settings the primitives draw with, kept by every draw target.
+pub struct DrawState {
+    // each clip rectangle is already intersected with the ones below it
+    clips: Vec<BoundingBox>,
+    blend_mode: BlendMode,
+}
+
+impl DrawState {
+    pub fn new() -> DrawState {
+        DrawState {
+            clips: Vec::new(),
+            blend_mode: BlendMode::SourceOver,
+        }
+    }
+
+    pub fn get_blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    // Returns the previous mode, so it can be restored.
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) -> BlendMode {
+        let previous = self.blend_mode;
+        self.blend_mode = blend_mode;
+        previous
+    }
+
+    /// Restricts drawing to `area` within the current clip rectangle until
+    /// the matching `pop_clip`.
+    pub fn push_clip(&mut self, area: &BoundingBox) {
+        let clip = match self.clips.last() {
+            Some(current) => {
+                current
+                    .intersection(area)
+                    .unwrap_or(BoundingBox {
+                                   x: area.x,
+                                   y: area.y,
+                                   width: 0,
+                                   height: 0,
+                               })
+            }
+            None => area.clone(),
+        };
+        self.clips.push(clip);
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+
+    // Returns the area drawing is restricted to, None if it is not.
+    pub fn get_clip(&self) -> Option<&BoundingBox> {
+        self.clips.last()
+    }
+
+    pub fn is_visible(&self, x: i32, y: i32) -> bool {
+        match self.clips.last() {
+            Some(clip) => clip.is_in_bound(x, y),
+            None => true,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Loading of fonts in the Glyph Bitmap Distribution Format.
+//!
+//! Only what is needed to draw the glyphs is read: `FONT_ASCENT` and
+//! `FONT_DESCENT` from the properties and `ENCODING`, `DWIDTH`, `BBX` and
+//! `BITMAP` of every character. Characters without a Unicode encoding are
+//! skipped.
+
+use collections::Vec;
+use core::char;
+use core::fmt;
+
+use font::BitmapGlyph;
+use font::Font;
+use font::Glyphs;
+
+// larger glyphs are no bitmap font anymore, the bound keeps the size of the
+// rows from overflowing
+const MAX_GLYPH_SIZE: i32 = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    // 1-based line number of the invalid entry
+    pub line: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid BDF entry in line {}", self.line)
+    }
+}
+
+pub fn parse(text: &str) -> Result<Font, ParseError> {
+    let mut ascent = None;
+    let mut descent = None;
+    let mut glyphs = Vec::new();
+    let mut glyph: Option<BitmapGlyph> = None;
+    let mut in_bitmap = false;
+
+    for (i, line) in text.lines().enumerate() {
+        let error = ParseError { line: i + 1 };
+        let mut fields = line.split_whitespace();
+        let keyword = match fields.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+
+        if in_bitmap {
+            if keyword == "ENDCHAR" {
+                in_bitmap = false;
+                if let Some(mut done) = glyph.take() {
+                    // rows that are missing stay empty
+                    let size = ((done.width + 7) / 8 * done.height) as usize;
+                    done.rows.resize(size, 0);
+                    glyphs.push(done);
+                }
+            } else if let Some(ref mut current) = glyph {
+                let row = parse_row(keyword, current.width).ok_or(error)?;
+                current.rows.extend(row);
+            }
+            continue;
+        }
+
+        if keyword == "STARTCHAR" {
+            glyph = Some(BitmapGlyph {
+                character: '\0',
+                width: 0,
+                height: 0,
+                x_offset: 0,
+                y_offset: 0,
+                advance: 0,
+                rows: Vec::new(),
+            });
+            continue;
+        }
+
+        let numbers = fields.map(|f| f.parse::<i32>().ok()).collect::<Option<Vec<_>>>();
+        let number = |n: usize| numbers.as_ref().and_then(|numbers| numbers.get(n).cloned());
+        match keyword {
+            "FONT_ASCENT" => ascent = number(0),
+            "FONT_DESCENT" => descent = number(0),
+            "ENCODING" | "DWIDTH" | "BBX" | "BITMAP" => {
+                let current = match glyph {
+                    Some(ref mut current) => current,
+                    None => return Err(error),
+                };
+                match keyword {
+                    "ENCODING" => {
+                        let code = number(0).ok_or(error)?;
+                        current.character = char::from_u32(code as u32).unwrap_or('\0');
+                    }
+                    "DWIDTH" => current.advance = number(0).ok_or(error)?,
+                    "BBX" => {
+                        match (number(0), number(1), number(2), number(3)) {
+                            (Some(width), Some(height), Some(x_offset), Some(y_offset))
+                                if width >= 0 && width <= MAX_GLYPH_SIZE && height >= 0 &&
+                                   height <= MAX_GLYPH_SIZE => {
+                                current.width = width;
+                                current.height = height;
+                                current.x_offset = x_offset;
+                                current.y_offset = y_offset;
+                            }
+                            _ => return Err(error),
+                        }
+                    }
+                    _ => in_bitmap = true,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let (ascent, descent) = match (ascent, descent) {
+        (Some(ascent), Some(descent)) => (ascent, descent),
+        _ => return Err(ParseError { line: text.lines().count() }),
+    };
+
+    glyphs.retain(|glyph| glyph.character != '\0');
+    glyphs.sort_by_key(|glyph| glyph.character);
+    glyphs.dedup_by_key(|glyph| glyph.character);
+
+    Ok(Font {
+        ascent: ascent,
+        descent: descent,
+        glyphs: Glyphs::Bitmaps(glyphs),
+    })
+}
+
+// A row of the bitmap as hex digits, padded to whole bytes.
+fn parse_row(hex: &str, width: i32) -> Option<Vec<u8>> {
+    let bytes = ((width + 7) / 8) as usize;
+    if hex.len() < bytes * 2 || !hex.chars().all(|c| c.is_digit(16)) {
+        return None;
+    }
+
+    (0..bytes)
+        .map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use font::text_size;
+
+    /// A font with a plus and a comma that reaches below the baseline.
+    pub const TINY: &'static str = "STARTFONT 2.1
+FONT tiny
+SIZE 4 75 75
+FONTBOUNDINGBOX 3 4 0 -1
+STARTPROPERTIES 2
+FONT_ASCENT 3
+FONT_DESCENT 1
+ENDPROPERTIES
+CHARS 2
+STARTCHAR plus
+ENCODING 43
+DWIDTH 4 0
+BBX 3 3 0 0
+BITMAP
+40
+E0
+40
+ENDCHAR
+STARTCHAR comma
+ENCODING 44
+DWIDTH 2 0
+BBX 1 2 0 -1
+BITMAP
+80
+80
+ENDCHAR
+ENDFONT
+";
+
+    #[test]
+    fn glyphs() {
+        let tiny = parse(TINY).unwrap();
+        assert_eq!((tiny.ascent, tiny.descent), (3, 1));
+        assert_eq!(text_size(&tiny, 1, "+,"), (6, 4));
+
+        let plus = tiny.glyph('+').unwrap();
+        assert!(plus.is_set(1, 0));
+        assert!(!plus.is_set(0, 0));
+        assert_eq!(tiny.glyph(',').unwrap().y_offset, -1);
+    }
+
+    #[test]
+    fn invalid_entries() {
+        assert_eq!(parse("STARTCHAR x\nBBX 1 x 0 0\n").err(),
+                   Some(ParseError { line: 2 }));
+        // glyphs that would not fit into memory fail before they are allocated
+        assert_eq!(parse("STARTCHAR x\nBBX 65536 65536 0 0\nBITMAP\nENDCHAR\n").err(),
+                   Some(ParseError { line: 2 }));
+        // without ascent and descent the error is at the end
+        assert_eq!(parse("STARTFONT 2.1\nENDFONT\n").err(), Some(ParseError { line: 2 }));
+    }
+}
@@ -0,0 +1,247 @@
+//! Placing text in a box: alignment, word wrap, line spacing and ellipsis.
+
+use collections::Vec;
+use core::cmp::max;
+
+use draw::Color;
+use draw::DrawTarget;
+use font::Font;
+use font::draw_text;
+use font::text_size;
+use util::bounding_box::BoundingBox;
+
+// Replaces the end of text that does not fit.
+const ELLIPSIS: &'static str = "...";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HorizontalAlign {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerticalAlign {
+    Top,
+    Center,
+    Bottom,
+}
+
+/// One line of laid out text, a part of the original text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub text: &'a str,
+    // the line is cut off and followed by the ellipsis
+    pub ellipsis: bool,
+}
+
+/// How text is placed in a box. Lines are broken at `\n` and, with `wrap`,
+/// at spaces or within words that are wider than the box. Text that does
+/// not fit is cut off, with `ellipsis` the last shown line ends in "...".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextLayout {
+    pub horizontal: HorizontalAlign,
+    pub vertical: VerticalAlign,
+    pub wrap: bool,
+    // extra pixels between two lines
+    pub line_spacing: i32,
+    pub ellipsis: bool,
+}
+
+impl TextLayout {
+    /// One centered line that ends in an ellipsis if it is too wide.
+    pub fn new() -> TextLayout {
+        TextLayout {
+            horizontal: HorizontalAlign::Center,
+            vertical: VerticalAlign::Center,
+            wrap: false,
+            line_spacing: 0,
+            ellipsis: true,
+        }
+    }
+
+    /// Breaks `text` into the lines that fit into `width` and `height`.
+    pub fn lines<'a>(&self,
+                     font: &Font,
+                     size: i32,
+                     text: &'a str,
+                     width: i32,
+                     height: i32)
+                     -> Vec<Line<'a>> {
+        let mut lines = Vec::new();
+        for paragraph in text.split('\n') {
+            if self.wrap {
+                wrap(font, size, paragraph, width, &mut lines);
+            } else {
+                lines.push(Line {
+                    text: paragraph,
+                    ellipsis: false,
+                });
+            }
+        }
+
+        // at least one line is shown, even if it is cut off
+        let line_height = font.line_height() * size;
+        let step = line_height + self.line_spacing;
+        let max_lines = if step > 0 {
+            max((height - line_height) / step + 1, 1) as usize
+        } else {
+            lines.len()
+        };
+        let cut = lines.len() > max_lines;
+        lines.truncate(max_lines);
+
+        if self.ellipsis {
+            let last = lines.len() - 1;
+            for (i, line) in lines.iter_mut().enumerate() {
+                let too_wide = text_size(font, size, line.text).0 > width;
+                if too_wide || (cut && i == last) {
+                    *line = truncate(font, size, line.text, width);
+                }
+            }
+        }
+
+        lines
+    }
+
+    /// Draws `text` into `area`, the text is not clipped to it.
+    pub fn draw(&self,
+                target: &mut DrawTarget,
+                area: &BoundingBox,
+                text: &str,
+                font: &Font,
+                size: i32,
+                color: Color) {
+        let lines = self.lines(font, size, text, area.width, area.height);
+        let line_height = font.line_height() * size;
+        let step = line_height + self.line_spacing;
+        let total = lines.len() as i32 * step - self.line_spacing;
+
+        let mut y = match self.vertical {
+            VerticalAlign::Top => area.y,
+            VerticalAlign::Center => area.y + (area.height - total) / 2,
+            VerticalAlign::Bottom => area.y + area.height - total,
+        };
+
+        for line in &lines {
+            let (text_width, _) = text_size(font, size, line.text);
+            let (ellipsis_width, _) = if line.ellipsis {
+                text_size(font, size, ELLIPSIS)
+            } else {
+                (0, 0)
+            };
+            let width = text_width + ellipsis_width;
+
+            let x = match self.horizontal {
+                HorizontalAlign::Left => area.x,
+                HorizontalAlign::Center => area.x + (area.width - width) / 2,
+                HorizontalAlign::Right => area.x + area.width - width,
+            };
+
+            draw_text(target, x, y, line.text, font, size, color);
+            if line.ellipsis {
+                draw_text(target, x + text_width, y, ELLIPSIS, font, size, color);
+            }
+            y += step;
+        }
+    }
+}
+
+// Greedy word wrap, words that are wider than a line are broken between
+// characters.
+fn wrap<'a>(font: &Font, size: i32, paragraph: &'a str, width: i32, lines: &mut Vec<Line<'a>>) {
+    let fits = |text: &str| text_size(font, size, text).0 <= width;
+    let mut rest = paragraph.trim_right();
+
+    loop {
+        if fits(rest) {
+            lines.push(Line {
+                text: rest,
+                ellipsis: false,
+            });
+            return;
+        }
+
+        // the last space at which the line still fits
+        let mut end = None;
+        for (i, c) in rest.char_indices() {
+            if c == ' ' && fits(rest[..i].trim_right()) {
+                end = Some(i);
+            }
+        }
+
+        let (line, next) = match end {
+            Some(end) => (rest[..end].trim_right(), &rest[end..]),
+            None => {
+                // as many characters as fit, but at least one
+                let mut split = rest.char_indices().nth(1).map_or(rest.len(), |(i, _)| i);
+                for (i, _) in rest.char_indices().skip(2) {
+                    if !fits(&rest[..i]) {
+                        break;
+                    }
+                    split = i;
+                }
+                (&rest[..split], &rest[split..])
+            }
+        };
+
+        lines.push(Line {
+            text: line,
+            ellipsis: false,
+        });
+        rest = next.trim_left();
+        if rest.is_empty() {
+            return;
+        }
+    }
+}
+
+// The longest start of `text` that fits into `width` together with the
+// ellipsis.
+fn truncate<'a>(font: &Font, size: i32, text: &'a str, width: i32) -> Line<'a> {
+    let available = width - text_size(font, size, ELLIPSIS).0;
+    let mut end = 0;
+    for (i, _) in text.char_indices().skip(1).chain(Some((text.len(), ' '))) {
+        if text_size(font, size, &text[..i]).0 > available {
+            break;
+        }
+        end = i;
+    }
+
+    Line {
+        text: text[..end].trim_right(),
+        ellipsis: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use font::BUILTIN;
+
+    fn text(lines: Vec<Line>) -> Vec<(String, bool)> {
+        lines.iter().map(|line| (line.text.to_string(), line.ellipsis)).collect()
+    }
+
+    #[test]
+    fn lines() {
+        let mut layout = TextLayout::new();
+
+        // one line is cut off with an ellipsis, every character is 6 pixels wide
+        assert_eq!(text(layout.lines(&BUILTIN, 1, "Zurück zum Menü", 50, 8)),
+                   vec![("Zurüc".to_string(), true)]);
+
+        layout.wrap = true;
+        assert_eq!(text(layout.lines(&BUILTIN, 1, "Zurück zum Menü", 40, 40)),
+                   vec![("Zurück".to_string(), false),
+                        ("zum".to_string(), false),
+                        ("Menü".to_string(), false)]);
+        // a word that is too long is broken
+        assert_eq!(text(layout.lines(&BUILTIN, 1, "Português", 30, 40)),
+                   vec![("Portu".to_string(), false), ("guês".to_string(), false)]);
+        // the last line that fits ends in an ellipsis
+        layout.line_spacing = 4;
+        assert_eq!(text(layout.lines(&BUILTIN, 1, "Zurück zum Menü", 40, 20)),
+                   vec![("Zurück".to_string(), false), ("zum".to_string(), true)]);
+    }
+}
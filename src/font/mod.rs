@@ -0,0 +1,196 @@
+//! Bitmap fonts that are rasterized into any draw target.
+//!
+//! The crate comes with a small built-in font, more fonts can be loaded from
+//! BDF files with `bdf::parse`. Glyphs are scaled by whole pixels, a size of
+//! 2 draws every font pixel as a 2x2 block. TrueType is not supported, its
+//! outlines are too expensive to rasterize per frame on the board.
+
+use collections::Vec;
+use collections::boxed::Box;
+
+use draw::Color;
+use draw::DrawTarget;
+use draw::fill_rectangle;
+
+pub mod bdf;
+mod builtin;
+pub mod layout;
+
+pub use self::builtin::BUILTIN;
+
+/// One character of a font, the bitmap is placed relative to the origin of
+/// the character on the baseline.
+pub struct Glyph<'a> {
+    pub width: i32,
+    pub height: i32,
+    // offset of the bottom left of the bitmap, y points up like in BDF
+    pub x_offset: i32,
+    pub y_offset: i32,
+    // distance to the origin of the next character
+    pub advance: i32,
+    bits: Bits<'a>,
+}
+
+enum Bits<'a> {
+    // one byte per column, the lowest bit is the top row
+    Columns(&'a [u8]),
+    // rows from the top, each padded to whole bytes with the highest bit left
+    Rows(&'a [u8]),
+}
+
+impl<'a> Glyph<'a> {
+    pub fn is_set(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return false;
+        }
+
+        match self.bits {
+            Bits::Columns(columns) => columns[x as usize] >> y & 1 != 0,
+            Bits::Rows(rows) => {
+                let stride = (self.width + 7) / 8;
+                rows[(y * stride + x / 8) as usize] >> (7 - x % 8) & 1 != 0
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Font {
+    // pixels above and below the baseline
+    pub ascent: i32,
+    pub descent: i32,
+    glyphs: Glyphs,
+}
+
+#[derive(Debug)]
+enum Glyphs {
+    // glyphs of 5x8 pixels sorted by character, see `builtin`
+    Columns(&'static [(char, [u8; 5])]),
+    Bitmaps(Vec<BitmapGlyph>),
+}
+
+#[derive(Debug)]
+struct BitmapGlyph {
+    character: char,
+    width: i32,
+    height: i32,
+    x_offset: i32,
+    y_offset: i32,
+    advance: i32,
+    rows: Vec<u8>,
+}
+
+impl Font {
+    pub fn line_height(&self) -> i32 {
+        self.ascent + self.descent
+    }
+
+    pub fn glyph(&self, character: char) -> Option<Glyph> {
+        match self.glyphs {
+            Glyphs::Columns(table) => {
+                table
+                    .binary_search_by_key(&character, |&(c, _)| c)
+                    .ok()
+                    .map(|i| {
+                        Glyph {
+                            width: 5,
+                            height: 8,
+                            x_offset: 0,
+                            y_offset: -self.descent,
+                            advance: 6,
+                            bits: Bits::Columns(&table[i].1),
+                        }
+                    })
+            }
+            Glyphs::Bitmaps(ref glyphs) => {
+                glyphs
+                    .binary_search_by_key(&character, |glyph| glyph.character)
+                    .ok()
+                    .map(|i| {
+                        let glyph = &glyphs[i];
+                        Glyph {
+                            width: glyph.width,
+                            height: glyph.height,
+                            x_offset: glyph.x_offset,
+                            y_offset: glyph.y_offset,
+                            advance: glyph.advance,
+                            bits: Bits::Rows(&glyph.rows),
+                        }
+                    })
+            }
+        }
+    }
+
+    // Characters the font does not have are shown as question marks.
+    fn glyph_or_fallback(&self, character: char) -> Option<Glyph> {
+        self.glyph(character).or_else(|| self.glyph('?'))
+    }
+
+    /// Keeps a loaded font for the rest of the program, so forms and themes
+    /// can refer to it like to the built-in one.
+    pub fn into_static(self) -> &'static Font {
+        unsafe { &*Box::into_raw(Box::new(self)) }
+    }
+}
+
+/// Width and height of `text` in one line.
+pub fn text_size(font: &Font, size: i32, text: &str) -> (i32, i32) {
+    let width = text.chars()
+        .filter_map(|c| font.glyph_or_fallback(c))
+        .map(|glyph| glyph.advance)
+        .sum::<i32>();
+
+    (width * size, font.line_height() * size)
+}
+
+/// Draws `text` in one line with the top left corner of the line at `x`,
+/// `y`, every font pixel as a `size`x`size` block.
+pub fn draw_text(target: &mut DrawTarget,
+                 x: i32,
+                 y: i32,
+                 text: &str,
+                 font: &Font,
+                 size: i32,
+                 color: Color) {
+    let baseline = y + font.ascent * size;
+    let mut origin = x;
+
+    for c in text.chars() {
+        let glyph = match font.glyph_or_fallback(c) {
+            Some(glyph) => glyph,
+            None => continue,
+        };
+
+        let top = baseline - (glyph.y_offset + glyph.height) * size;
+        let left = origin + glyph.x_offset * size;
+        for row in 0..glyph.height {
+            for column in 0..glyph.width {
+                if !glyph.is_set(column, row) {
+                    continue;
+                }
+
+                let (px, py) = (left + column * size, top + row * size);
+                if size == 1 {
+                    target.draw_pixel(px, py, color);
+                } else {
+                    fill_rectangle(target, px, py, size - 1, size - 1, color);
+                }
+            }
+        }
+
+        origin += glyph.advance * size;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_text_size() {
+        assert_eq!(text_size(&BUILTIN, 1, "Ab"), (12, 8));
+        assert_eq!(text_size(&BUILTIN, 2, "Ab"), (24, 16));
+        // unknown characters are drawn as question marks
+        assert_eq!(text_size(&BUILTIN, 1, "€"), (6, 8));
+    }
+}
@@ -1,58 +1,134 @@
 use collections::boxed::Box;
 use core::iter;
-use stm32f7::lcd::Color;
 
-use draw::draw_rectangle;
-use draw::fill_rectangle;
+use action::event::Event;
+use action::event::EventContext;
+use draw::DrawTarget;
+use draw::display;
 use forms::form::Clickable;
 use forms::form::Form;
+use forms::form::call_action;
+use forms::form::draw_child;
+use forms::label::Label;
+use forms::theme::Style;
+use forms::theme::Theme;
+use forms::theme::draw_styled_box;
 use util::bounding_box::BoundingBox;
-use util::sizes;
+use util::dirty_region::DirtyRegion;
+
+// The content of a button, a label is kept as such so its text can be
+// changed.
+enum Child {
+    Label(Label),
+    Form(Box<Form>),
+}
+
+impl Child {
+    fn form(&self) -> &Form {
+        match *self {
+            Child::Label(ref label) => label,
+            Child::Form(ref form) => &**form,
+        }
+    }
+
+    fn form_mut(&mut self) -> &mut Form {
+        match *self {
+            Child::Label(ref mut label) => label,
+            Child::Form(ref mut form) => &mut **form,
+        }
+    }
+}
 
 pub struct Button {
     bounding_box: BoundingBox,
     outer_bounding_box: BoundingBox,
-    child: Option<Box<Form>>,
-    on_click: Option<fn(form: &mut Button) -> ()>,
+    child: Option<Child>,
+    on_event: Option<Box<FnMut(&mut Button, &Event, &mut EventContext)>>,
     movable: bool,
-    border_width: i32,
-    border_color: Color,
+    style: Style,
+    pressed: bool,
 }
 
 impl Button {
     pub fn new(bounding_box: BoundingBox) -> Button {
         Button {
             bounding_box: bounding_box,
-            outer_bounding_box: BoundingBox {
-                x: 0,
-                y: 0,
-                width: sizes::RES_X,
-                height: sizes::RES_Y,
-            },
+            outer_bounding_box: display::current().get_bounding_box(),
             child: None,
-            on_click: None,
+            on_event: None,
             movable: false,
-            border_width: 2,
-            border_color: Color::from_hex(0xFFFFFF),
+            style: Theme::dark().button,
+            pressed: false,
         }
     }
 
-    pub fn set_action_on_click(&mut self, callback: fn(form: &mut Button) -> ()) -> () {
-        self.on_click = Some(callback);
+    /// Calls `callback` whenever the button is clicked.
+    pub fn set_action_on_click<F>(&mut self, mut callback: F) -> ()
+        where F: FnMut(&mut Button, &mut EventContext) + 'static
+    {
+        self.set_event_handler(move |button, event, ctx| {
+            if let Event::Clicked { .. } = *event {
+                callback(button, ctx);
+            }
+        });
     }
 
-    pub fn set_child(&mut self, mut child: Box<Form>) -> () {
-        child
-            .get_bounding_box()
-            .rebase_to_outer_box(self.get_bounding_box());
+    /// Calls `handler` for every event dispatched to the button, replaces a
+    /// previously set handler or click action.
+    pub fn set_event_handler<F>(&mut self, handler: F) -> ()
+        where F: FnMut(&mut Button, &Event, &mut EventContext) + 'static
+    {
+        self.on_event = Some(Box::new(handler));
+    }
 
-        child.set_outer_bounding_box(self.bounding_box.clone());
+    pub fn set_child(&mut self, child: Box<Form>) -> () {
+        self.place_child(Child::Form(child));
+    }
 
-        self.child = Some(child);
+    /// Sets a label as the child, its text can then be changed with
+    /// `set_text`.
+    pub fn set_label(&mut self, label: Label) -> () {
+        self.place_child(Child::Label(label));
+    }
+
+    pub fn get_child(&mut self) -> Option<&mut Form> {
+        match self.child {
+            Some(ref mut child) => Some(child.form_mut()),
+            None => None,
+        }
+    }
+
+    pub fn get_label(&mut self) -> Option<&mut Label> {
+        match self.child {
+            Some(Child::Label(ref mut label)) => Some(label),
+            _ => None,
+        }
+    }
+
+    /// Changes the text of the label in the button, if it has one.
+    pub fn set_text(&mut self, text: &str, dirty: &mut DirtyRegion) -> () {
+        if let Some(label) = self.get_label() {
+            label.set_text(text, dirty);
+        }
+    }
+
+    // True while a finger that went down on the button is on the panel.
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    pub fn get_style(&self) -> &Style {
+        &self.style
+    }
+
+    /// Overrides the style of the theme until the next one is applied.
+    pub fn set_style(&mut self, style: Style) -> () {
+        self.style = style;
+        self.layout_child();
     }
 
     pub fn get_border_width(&self) -> i32 {
-        self.border_width
+        self.style.border_width
     }
 
     pub fn set_border_width(&mut self, width: i32) -> () {
@@ -60,7 +136,24 @@ impl Button {
             return;
         }
 
-        self.border_width = width;
+        self.style.border_width = width;
+        self.layout_child();
+    }
+
+    fn place_child(&mut self, mut child: Child) {
+        let content = self.style.content_box(&self.bounding_box);
+        child.form_mut().get_bounding_box().rebase_to_outer_box(&content);
+        child.form_mut().set_outer_bounding_box(content);
+
+        self.child = Some(child);
+    }
+
+    // The child fills the button within the border and padding.
+    fn layout_child(&mut self) {
+        let content = self.style.content_box(&self.bounding_box);
+        if let Some(ref mut child) = self.child {
+            child.form_mut().set_outer_bounding_box(content);
+        }
     }
 }
 
@@ -71,10 +164,7 @@ impl Form for Button {
 
     fn set_bounding_box(&mut self, bounding_box: BoundingBox) -> () {
         self.bounding_box = bounding_box;
-
-        if let Some(ref mut child) = self.child {
-            child.set_outer_bounding_box(self.bounding_box.clone());
-        }
+        self.layout_child();
     }
 
     fn set_outer_bounding_box(&mut self, bounding_box: BoundingBox) {
@@ -84,12 +174,12 @@ impl Form for Button {
     fn get_children<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Form> + 'a> {
         match self.child {
             None => Box::new(iter::empty()),
-            Some(ref mut child) => Box::new(iter::once::<&'a mut Form>(&mut **child)),
+            Some(ref mut child) => Box::new(iter::once(child.form_mut())),
         }
     }
 
     fn is_clickable(&mut self) -> Option<&mut Clickable> {
-        match self.on_click {
+        match self.on_event {
             None => None,
             _ => Some(self),
         }
@@ -103,32 +193,21 @@ impl Form for Button {
         self.movable = value;
     }
 
-    fn clear(&self) -> () {
-        fill_rectangle(self.bounding_box.x,
-                       self.bounding_box.y,
-                       self.bounding_box.width,
-                       self.bounding_box.height,
-                       Color::rgba(0, 0, 0, 0));
-
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box.with_outline());
     }
 
-    fn draw(&self) -> () {
-        for i in 0i32..self.border_width {
-            draw_rectangle(self.bounding_box.x + i,
-                           self.bounding_box.y + i,
-                           self.bounding_box.width - (2 * i),
-                           self.bounding_box.height - (2 * i),
-                           self.border_color);
-        }
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        draw_styled_box(target, &self.bounding_box, &self.style, self.pressed);
 
         match self.child {
             None => (),
-            Some(ref child) => child.draw(),
+            Some(ref child) => draw_child(target, &self.bounding_box, child.form()),
         }
     }
 
-    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool) {
-        self.clear();
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box);
 
         let outer_if_top = if top {
             Some(&self.outer_bounding_box)
@@ -139,20 +218,37 @@ impl Form for Button {
         let (delta_x, delta_y) = self.bounding_box
             .move_in_direction(dir_x, dir_y, outer_if_top);
 
+        let content = self.style.content_box(&self.bounding_box);
         if let Some(ref mut child) = self.child {
-            child.set_outer_bounding_box(self.bounding_box.clone());
-            child.move_form(delta_x, delta_y, false);
+            child.form_mut().set_outer_bounding_box(content);
+            child.form_mut().move_form(delta_x, delta_y, false, dirty);
         }
 
-        self.draw();
+        dirty.add(&self.bounding_box);
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> () {
+        self.style = theme.button;
+        self.layout_child();
+        if let Some(ref mut child) = self.child {
+            child.form_mut().apply_theme(theme);
+        }
     }
 }
 
 impl Clickable for Button {
-    fn click(&mut self) {
-        match self.on_click {
-            Some(func) => func(self),
-            None => (),
+    fn handle_event(&mut self, event: &Event, ctx: &mut EventContext) {
+        match *event {
+            Event::Pressed { .. } => self.pressed = true,
+            Event::Released { .. } => self.pressed = false,
+            _ => (),
         }
+
+        call_action(self,
+                    |button| &mut button.on_event,
+                    |handler, button| handler(button, event, ctx));
+
+        // the handler may change anything about the button
+        ctx.invalidate(&self.bounding_box);
     }
 }
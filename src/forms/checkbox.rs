@@ -0,0 +1,195 @@
+use collections::String;
+use collections::boxed::Box;
+use core::cmp::max;
+use core::cmp::min;
+use core::iter;
+
+use action::event::Event;
+use action::event::EventContext;
+use draw::DrawTarget;
+use draw::draw_thick_line;
+use forms::form::Clickable;
+use forms::form::Form;
+use forms::form::call_action;
+use forms::theme::Style;
+use forms::theme::Theme;
+use forms::theme::draw_styled_box;
+use forms::theme::draw_styled_text;
+use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
+
+// The box is as high as the form up to this size, the text starts this
+// far right of it.
+const BOX_SIZE: i32 = 20;
+const TEXT_GAP: i32 = 8;
+
+/// A box that is checked and unchecked by clicking it or its text.
+pub struct Checkbox {
+    bounding_box: BoundingBox,
+    outer_bounding_box: BoundingBox,
+    movable: bool,
+    text: String,
+    checked: bool,
+    pressed: bool,
+    on_change: Option<Box<FnMut(&mut Checkbox, &mut EventContext)>>,
+    // the box and the check mark
+    style: Style,
+    text_style: Style,
+}
+
+impl Checkbox {
+    pub fn new(bounding_box: BoundingBox, text: &str, checked: bool) -> Checkbox {
+        let theme = Theme::dark();
+        Checkbox {
+            bounding_box: bounding_box.clone(),
+            outer_bounding_box: bounding_box,
+            movable: false,
+            text: String::from(text),
+            checked: checked,
+            pressed: false,
+            on_change: None,
+            style: theme.button,
+            text_style: theme.label,
+        }
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    /// Checks or unchecks the box without calling the change action.
+    pub fn set_checked(&mut self, checked: bool, dirty: &mut DirtyRegion) -> () {
+        if self.checked != checked {
+            self.checked = checked;
+            dirty.add(&self.bounding_box);
+        }
+    }
+
+    /// Calls `callback` after the box was checked or unchecked by a click.
+    pub fn set_action_on_change<F>(&mut self, callback: F) -> ()
+        where F: FnMut(&mut Checkbox, &mut EventContext) + 'static
+    {
+        self.on_change = Some(Box::new(callback));
+    }
+
+    // The square on the left, vertically centered.
+    fn box_area(&self) -> BoundingBox {
+        let size = min(self.bounding_box.height, BOX_SIZE);
+        BoundingBox {
+            x: self.bounding_box.x,
+            y: self.bounding_box.y + (self.bounding_box.height - size) / 2,
+            width: size,
+            height: size,
+        }
+    }
+}
+
+impl Form for Checkbox {
+    fn get_bounding_box(&mut self) -> &mut BoundingBox {
+        &mut self.bounding_box
+    }
+
+    fn set_bounding_box(&mut self, bounding_box: BoundingBox) -> () {
+        self.bounding_box = bounding_box;
+    }
+
+    fn set_outer_bounding_box(&mut self, bounding_box: BoundingBox) {
+        self.outer_bounding_box = bounding_box;
+    }
+
+    fn get_children<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Form> + 'a> {
+        Box::new(iter::empty())
+    }
+
+    fn is_clickable(&mut self) -> Option<&mut Clickable> {
+        Some(self)
+    }
+
+    fn is_movable(&mut self) -> bool {
+        self.movable
+    }
+
+    fn set_movable(&mut self, value: bool) -> () {
+        self.movable = value;
+    }
+
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box.with_outline());
+    }
+
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        let area = self.box_area();
+        let style = Style {
+            corner_radius: min(self.style.corner_radius, area.width / 4),
+            ..self.style
+        };
+        draw_styled_box(target, &area, &style, self.pressed);
+
+        if self.checked {
+            // a tick from the left over the bottom to the top right
+            let (x, y, size) = (area.x, area.y, area.width);
+            let thickness = max(2, size / 8);
+            draw_thick_line(target,
+                            x + size / 5,
+                            y + size / 2,
+                            x + size * 2 / 5,
+                            y + size * 3 / 4,
+                            thickness,
+                            self.style.text);
+            draw_thick_line(target,
+                            x + size * 2 / 5,
+                            y + size * 3 / 4,
+                            x + size * 4 / 5,
+                            y + size / 4,
+                            thickness,
+                            self.style.text);
+        }
+
+        let inset = area.width + TEXT_GAP;
+        let text_area = BoundingBox {
+            x: self.bounding_box.x + inset,
+            width: self.bounding_box.width - inset,
+            ..self.bounding_box.clone()
+        };
+        draw_styled_text(target, &text_area, &self.text, &self.text_style);
+    }
+
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box);
+
+        let outer_if_top = if top {
+            Some(&self.outer_bounding_box)
+        } else {
+            None
+        };
+
+        self.bounding_box
+            .move_in_direction(dir_x, dir_y, outer_if_top);
+
+        dirty.add(&self.bounding_box);
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> () {
+        self.style = theme.button;
+        self.text_style = theme.label;
+    }
+}
+
+impl Clickable for Checkbox {
+    fn handle_event(&mut self, event: &Event, ctx: &mut EventContext) {
+        match *event {
+            Event::Pressed { .. } => self.pressed = true,
+            Event::Released { .. } => self.pressed = false,
+            Event::Clicked { .. } => {
+                self.checked = !self.checked;
+
+                call_action(self,
+                            |checkbox| &mut checkbox.on_change,
+                            |action, checkbox| action(checkbox, ctx));
+            }
+            _ => return,
+        }
+
+        ctx.invalidate(&self.bounding_box);
+    }
+}
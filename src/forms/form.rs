@@ -1,7 +1,12 @@
 use collections::boxed::Box;
 use collections::Vec;
 
+use action::event::Event;
+use action::event::EventContext;
+use draw::DrawTarget;
+use forms::theme::Theme;
 use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
 
 pub trait Form {
     fn get_bounding_box(&mut self) -> &mut BoundingBox;
@@ -9,13 +14,79 @@ pub trait Form {
     fn set_outer_bounding_box(&mut self, bounding_box: BoundingBox);
     fn get_children<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Form> + 'a>;
     fn is_clickable(&mut self) -> Option<&mut Clickable>;
+    // Forms that do not take the focus when pressed leave it where it is,
+    // e.g. the keyboard typing into the focused text field.
+    fn takes_focus(&mut self) -> bool {
+        true
+    }
     fn is_movable(&mut self) -> bool;
     fn set_movable(&mut self, value: bool) -> ();
-    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool);
-    fn clear(&self) -> ();
-    fn draw(&self) -> ();
+    // Moves the form and invalidates its old and new area, the form is drawn
+    // again with the next frame.
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion);
+    fn clear(&self, target: &mut DrawTarget) -> ();
+    fn draw(&self, target: &mut DrawTarget) -> ();
+
+    // Draws the parts of the form that intersect `region`. Containers
+    // override this to only redraw the affected children.
+    fn redraw(&mut self, target: &mut DrawTarget, region: &BoundingBox) -> () {
+        if self.get_bounding_box().intersects(region) {
+            self.draw(target);
+        }
+    }
+
+    // Advances animations to `ticks` and invalidates what changed. Called
+    // once per main loop iteration, containers pass it on to their children.
+    fn tick(&mut self, ticks: usize, dirty: &mut DirtyRegion) -> () {
+        for child in self.get_children() {
+            child.tick(ticks, dirty);
+        }
+    }
+
+    // Takes the colors and measures of the form from `theme` and passes it
+    // on to the children. The form has to be invalidated afterwards.
+    fn apply_theme(&mut self, theme: &Theme) -> () {
+        for child in self.get_children() {
+            child.apply_theme(theme);
+        }
+    }
+}
+
+/// Draws `child` clipped to `area`, usually the bounding box of its parent,
+/// so it cannot paint over the siblings of the parent.
+pub fn draw_child(target: &mut DrawTarget, area: &BoundingBox, child: &Form) {
+    target.push_clip(&area.with_outline());
+    child.draw(target);
+    target.pop_clip();
+}
+
+/// Calls the action in the field of `form` that `slot` returns, through
+/// `call` and with the form itself. The action is taken out of the form
+/// meanwhile and put back afterwards, unless it stored a new one.
+pub fn call_action<F, A: ?Sized, S, C>(form: &mut F, slot: S, call: C)
+    where S: Fn(&mut F) -> &mut Option<Box<A>>,
+          C: FnOnce(&mut A, &mut F)
+{
+    let action = slot(form).take();
+    if let Some(mut action) = action {
+        call(&mut *action, form);
+        let slot = slot(form);
+        if slot.is_none() {
+            *slot = Some(action);
+        }
+    }
+}
+
+/// Like `draw_child` for `Form::redraw`.
+pub fn redraw_child(target: &mut DrawTarget,
+                    area: &BoundingBox,
+                    child: &mut Form,
+                    region: &BoundingBox) {
+    target.push_clip(&area.with_outline());
+    child.redraw(target, region);
+    target.pop_clip();
 }
 
 pub trait Clickable {
-    fn click(&mut self) -> ();
+    fn handle_event(&mut self, event: &Event, ctx: &mut EventContext) -> ();
 }
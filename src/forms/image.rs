@@ -0,0 +1,192 @@
+use collections::boxed::Box;
+use core::iter;
+
+use draw::Color;
+use draw::DrawTarget;
+use forms::form::Clickable;
+use forms::form::Form;
+use image::Bitmap;
+use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
+
+/// How a bitmap is sized to the bounding box of the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scaling {
+    // the original size, centered
+    None,
+    // as large as possible while the whole bitmap is shown, centered
+    Fit,
+    // covers the whole box, the sides that stick out are cut off
+    Fill,
+    // stretched to the box, the aspect ratio is not kept
+    Stretch,
+}
+
+/// Shows a bitmap, scaled by nearest neighbor.
+pub struct Image {
+    bounding_box: BoundingBox,
+    outer_bounding_box: BoundingBox,
+    movable: bool,
+    bitmap: Bitmap,
+    scaling: Scaling,
+    // pixels of this color are not drawn
+    color_key: Option<Color>,
+}
+
+impl Image {
+    pub fn new(bounding_box: BoundingBox, bitmap: Bitmap) -> Image {
+        Image {
+            bounding_box: bounding_box.clone(),
+            outer_bounding_box: bounding_box,
+            movable: false,
+            bitmap: bitmap,
+            scaling: Scaling::Fit,
+            color_key: None,
+        }
+    }
+
+    /// Sets how the bitmap is sized, for setting up a new image.
+    /// `set_scaling` changes it later on.
+    pub fn with_scaling(mut self, scaling: Scaling) -> Image {
+        self.scaling = scaling;
+        self
+    }
+
+    /// Makes the pixels of `color` transparent, for setting up a new image.
+    /// `set_color_key` changes it later on.
+    pub fn with_color_key(mut self, color: Color) -> Image {
+        self.color_key = Some(color);
+        self
+    }
+
+    pub fn set_bitmap(&mut self, bitmap: Bitmap, dirty: &mut DirtyRegion) -> () {
+        self.bitmap = bitmap;
+        dirty.add(&self.bounding_box);
+    }
+
+    pub fn set_scaling(&mut self, scaling: Scaling, dirty: &mut DirtyRegion) -> () {
+        if self.scaling != scaling {
+            self.scaling = scaling;
+            dirty.add(&self.bounding_box);
+        }
+    }
+
+    /// Makes the pixels of `color` transparent, e.g. the magenta background
+    /// of icons that have no alpha channel.
+    pub fn set_color_key(&mut self, color: Option<Color>, dirty: &mut DirtyRegion) -> () {
+        if self.color_key != color {
+            self.color_key = color;
+            dirty.add(&self.bounding_box);
+        }
+    }
+
+    // Where the bitmap is drawn to, may be larger than the bounding box.
+    fn destination(&self) -> BoundingBox {
+        let area = &self.bounding_box;
+        let (width, height) = (self.bitmap.width(), self.bitmap.height());
+        if width == 0 || height == 0 {
+            return BoundingBox {
+                x: area.x,
+                y: area.y,
+                width: 0,
+                height: 0,
+            };
+        }
+
+        // the box is wider than the bitmap relative to their heights
+        let wider = area.width * height > area.height * width;
+        let (dest_width, dest_height) = match self.scaling {
+            Scaling::None => (width, height),
+            Scaling::Stretch => (area.width, area.height),
+            Scaling::Fit if wider => (width * area.height / height, area.height),
+            Scaling::Fit => (area.width, height * area.width / width),
+            Scaling::Fill if wider => (area.width, height * area.width / width),
+            Scaling::Fill => (width * area.height / height, area.height),
+        };
+
+        BoundingBox {
+            x: area.x + (area.width - dest_width) / 2,
+            y: area.y + (area.height - dest_height) / 2,
+            width: dest_width,
+            height: dest_height,
+        }
+    }
+}
+
+impl Form for Image {
+    fn get_bounding_box(&mut self) -> &mut BoundingBox {
+        &mut self.bounding_box
+    }
+
+    fn set_bounding_box(&mut self, bounding_box: BoundingBox) -> () {
+        self.bounding_box = bounding_box;
+    }
+
+    fn set_outer_bounding_box(&mut self, bounding_box: BoundingBox) {
+        self.outer_bounding_box = bounding_box.clone();
+        self.set_bounding_box(bounding_box);
+    }
+
+    fn get_children<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Form> + 'a> {
+        Box::new(iter::empty())
+    }
+
+    fn is_clickable(&mut self) -> Option<&mut Clickable> {
+        None
+    }
+
+    fn is_movable(&mut self) -> bool {
+        self.movable
+    }
+
+    fn set_movable(&mut self, value: bool) -> () {
+        self.movable = value;
+    }
+
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box);
+    }
+
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        let destination = self.destination();
+        let visible = match destination.intersection(&self.bounding_box) {
+            Some(visible) => visible,
+            None => return,
+        };
+
+        // unscaled bitmaps are drawn as a whole, which the DMA2D speeds up
+        if destination.width == self.bitmap.width() &&
+           destination.height == self.bitmap.height() && self.color_key.is_none() {
+            target.push_clip(&visible);
+            target.draw_bitmap(destination.x, destination.y, &self.bitmap);
+            target.pop_clip();
+            return;
+        }
+
+        for y in visible.y..visible.y + visible.height {
+            let source_y = (y - destination.y) * self.bitmap.height() / destination.height;
+            for x in visible.x..visible.x + visible.width {
+                let source_x = (x - destination.x) * self.bitmap.width() / destination.width;
+                let color = self.bitmap.get_pixel(source_x, source_y);
+                if Some(color) != self.color_key {
+                    target.draw_pixel(x, y, color);
+                }
+            }
+        }
+    }
+
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box);
+
+        let outer_if_top = if top {
+            Some(&self.outer_bounding_box)
+        } else {
+            None
+        };
+
+        self.bounding_box
+            .move_in_direction(dir_x, dir_y, outer_if_top);
+
+        dirty.add(&self.bounding_box);
+    }
+}
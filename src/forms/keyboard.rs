@@ -0,0 +1,458 @@
+use collections::Vec;
+use collections::boxed::Box;
+use core::iter;
+
+use action::event::Event;
+use action::event::EventContext;
+use action::event::Key;
+use draw::DrawTarget;
+use font::layout::TextLayout;
+use forms::button::Button;
+use forms::form::Clickable;
+use forms::form::Form;
+use forms::form::draw_child;
+use forms::label::Label;
+use forms::theme::Theme;
+use forms::theme::draw_styled_box;
+use layout::HorizontalLayout;
+use layout::VerticalLayout;
+use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
+
+// The digits and symbols that replace the letters on the second page.
+const SYMBOLS: [&'static str; 3] = ["1234567890", "@#&*-+=()", "!?'\":;/"];
+
+/// The arrangement of the letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyboardLayout {
+    Qwerty,
+    // German, with umlauts
+    Qwertz,
+    // French
+    Azerty,
+}
+
+impl KeyboardLayout {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            KeyboardLayout::Qwerty => "QWERTY",
+            KeyboardLayout::Qwertz => "QWERTZ",
+            KeyboardLayout::Azerty => "AZERTY",
+        }
+    }
+
+    // The three rows of lowercase letters.
+    fn letters(&self) -> [&'static str; 3] {
+        match *self {
+            KeyboardLayout::Qwerty => ["qwertyuiop", "asdfghjkl", "zxcvbnm"],
+            KeyboardLayout::Qwertz => ["qwertzuiopü", "asdfghjklöä", "yxcvbnm"],
+            KeyboardLayout::Azerty => ["azertyuiop", "qsdfghjklm", "wxcvbn'"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shift {
+    Off,
+    // for the next letter only
+    Once,
+    // caps lock
+    Locked,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Page {
+    Letters,
+    Symbols,
+}
+
+// What a key does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cap {
+    Char(char),
+    Shift,
+    Backspace,
+    // switches between the letters and the symbols
+    Page,
+    Space,
+    Enter,
+}
+
+/// An on-screen keyboard that types into the focused form, e.g. a
+/// `TextField`, by sending it key events.
+///
+/// The keys are buttons in rows of a vertical layout, they are built again
+/// whenever their labels change. The keyboard itself handles the touches of
+/// all keys and does not take the focus, so it stays with the text field.
+/// A key is typed when the finger is lifted on it, a popup above it shows
+/// the letter while it is pressed.
+pub struct Keyboard {
+    bounding_box: BoundingBox,
+    outer_bounding_box: BoundingBox,
+    movable: bool,
+    rows: VerticalLayout,
+    layout: KeyboardLayout,
+    page: Page,
+    shift: Shift,
+    // the key under the finger and where it is
+    pressed: Option<(Cap, BoundingBox)>,
+    popups: bool,
+    auto_capitalization: bool,
+    double_space_period: bool,
+    // the characters typed last, the last one second
+    typed: (Option<char>, Option<char>),
+    theme: Theme,
+}
+
+impl Keyboard {
+    pub fn new(bounding_box: BoundingBox, layout: KeyboardLayout) -> Keyboard {
+        let mut keyboard = Keyboard {
+            bounding_box: bounding_box.clone(),
+            outer_bounding_box: bounding_box.clone(),
+            movable: false,
+            rows: VerticalLayout::new(bounding_box),
+            layout: layout,
+            page: Page::Letters,
+            shift: Shift::Off,
+            pressed: None,
+            popups: true,
+            auto_capitalization: false,
+            double_space_period: false,
+            typed: (None, None),
+            theme: Theme::dark(),
+        };
+        keyboard.build();
+        keyboard
+    }
+
+    pub fn get_layout(&self) -> KeyboardLayout {
+        self.layout
+    }
+
+    pub fn set_layout(&mut self, layout: KeyboardLayout, dirty: &mut DirtyRegion) -> () {
+        self.layout = layout;
+        self.rebuild(dirty);
+    }
+
+    /// Shows the pressed letter enlarged above its key, on by default.
+    pub fn set_popups(&mut self, popups: bool) -> () {
+        self.popups = popups;
+    }
+
+    /// Shifts the first letter of the text and of every sentence.
+    pub fn set_auto_capitalization(&mut self, value: bool) -> () {
+        self.auto_capitalization = value;
+        if value && self.typed == (None, None) && self.shift == Shift::Off {
+            self.shift = Shift::Once;
+            self.build();
+        }
+    }
+
+    /// Turns two spaces after a word into a period and a space.
+    pub fn set_double_space_period(&mut self, value: bool) -> () {
+        self.double_space_period = value;
+    }
+
+    // The keys of the current page, row by row.
+    fn caps(&self) -> Vec<Vec<Cap>> {
+        let rows = match self.page {
+            Page::Letters => self.layout.letters(),
+            Page::Symbols => SYMBOLS,
+        };
+
+        let mut caps: Vec<Vec<Cap>> =
+            rows.iter().map(|row| row.chars().map(Cap::Char).collect()).collect();
+        caps[2].insert(0, Cap::Shift);
+        caps[2].push(Cap::Backspace);
+        caps.push(vec![Cap::Page, Cap::Char(','), Cap::Space, Cap::Char('.'), Cap::Enter]);
+        caps
+    }
+
+    // The text on the key, characters are encoded into `buffer`.
+    fn text<'a>(&self, cap: Cap, buffer: &'a mut [u8; 4]) -> &'a str {
+        match cap {
+            Cap::Char(c) => self.shifted(c).encode_utf8(buffer),
+            Cap::Shift => {
+                match self.shift {
+                    Shift::Off => "Shift",
+                    Shift::Once => "SHIFT",
+                    Shift::Locked => "CAPS",
+                }
+            }
+            Cap::Backspace => "<-",
+            Cap::Page if self.page == Page::Letters => "?123",
+            Cap::Page => "ABC",
+            Cap::Space => self.layout.name(),
+            Cap::Enter => "Enter",
+        }
+    }
+
+    fn shifted(&self, c: char) -> char {
+        if self.page == Page::Letters && self.shift != Shift::Off {
+            upper(c)
+        } else {
+            c
+        }
+    }
+
+    // Creates the rows of buttons for the current page, shift and theme.
+    fn build(&mut self) {
+        let mut rows = VerticalLayout::new(self.bounding_box.clone());
+        for caps in self.caps() {
+            let mut row = HorizontalLayout::new(self.bounding_box.clone());
+            let mut proportions = Vec::new();
+            for &cap in &caps {
+                let mut buffer = [0; 4];
+                let label = Label::new(self.bounding_box.clone(), self.text(cap, &mut buffer));
+                let mut key = Button::new(self.bounding_box.clone());
+                key.set_label(label);
+                row.add_form(Box::new(key));
+                proportions.push(match cap {
+                                     Cap::Char(_) => 2,
+                                     Cap::Space => 8,
+                                     _ => 3,
+                                 });
+            }
+            row.set_proportions(proportions);
+            rows.add_form(Box::new(row));
+        }
+
+        rows.apply_theme(&self.theme);
+        self.rows = rows;
+    }
+
+    fn rebuild(&mut self, dirty: &mut DirtyRegion) {
+        self.build();
+        dirty.add(&self.bounding_box.with_outline());
+    }
+
+    fn set_shift(&mut self, shift: Shift, dirty: &mut DirtyRegion) {
+        if self.shift != shift {
+            self.shift = shift;
+            self.rebuild(dirty);
+        }
+    }
+
+    // The key at the screen position and its bounding box.
+    fn key_at(&mut self, x: i32, y: i32) -> Option<(Cap, BoundingBox)> {
+        let caps = self.caps();
+        for (row, caps) in self.rows.get_children().zip(caps.iter()) {
+            for (key, &cap) in row.get_children().zip(caps.iter()) {
+                let bounding_box = key.get_bounding_box();
+                if bounding_box.is_in_bound(x, y) {
+                    return Some((cap, bounding_box.clone()));
+                }
+            }
+        }
+        None
+    }
+
+    // The area the pressed key is drawn to, with the popup above it.
+    fn pressed_area(&self) -> Option<BoundingBox> {
+        match self.pressed {
+            Some((Cap::Char(_), ref key)) if self.popups => {
+                Some(BoundingBox {
+                         x: key.x - 4,
+                         y: key.y - key.height,
+                         width: key.width + 8,
+                         height: 2 * key.height,
+                     })
+            }
+            Some((_, ref key)) => Some(key.clone()),
+            None => None,
+        }
+    }
+
+    // Sends `key` to the focused form and remembers the typed characters.
+    fn send(&mut self, key: Key, ctx: &mut EventContext) {
+        ctx.send_key(key);
+        self.typed = match key {
+            Key::Char(c) => (self.typed.1, Some(c)),
+            _ => (None, None),
+        };
+    }
+
+    fn type_cap(&mut self, cap: Cap, ctx: &mut EventContext) {
+        match cap {
+            Cap::Char(c) => {
+                let c = self.shifted(c);
+                self.send(Key::Char(c), ctx);
+                if self.shift == Shift::Once {
+                    self.set_shift(Shift::Off, ctx.get_dirty_region());
+                }
+            }
+            Cap::Shift => {
+                let shift = match self.shift {
+                    Shift::Off => Shift::Once,
+                    Shift::Once => Shift::Locked,
+                    Shift::Locked => Shift::Off,
+                };
+                self.set_shift(shift, ctx.get_dirty_region());
+            }
+            Cap::Backspace => self.send(Key::Backspace, ctx),
+            Cap::Page => {
+                self.page = match self.page {
+                    Page::Letters => Page::Symbols,
+                    Page::Symbols => Page::Letters,
+                };
+                self.rebuild(ctx.get_dirty_region());
+            }
+            Cap::Space => {
+                let after_word = match self.typed {
+                    (Some(before), Some(' ')) => before != ' ',
+                    _ => false,
+                };
+                if self.double_space_period && after_word {
+                    self.send(Key::Backspace, ctx);
+                    self.send(Key::Char('.'), ctx);
+                }
+
+                let sentence_end = match self.typed.1 {
+                    Some('.') | Some('!') | Some('?') => true,
+                    _ => false,
+                };
+                self.send(Key::Char(' '), ctx);
+                if self.auto_capitalization && sentence_end && self.shift == Shift::Off {
+                    self.set_shift(Shift::Once, ctx.get_dirty_region());
+                }
+            }
+            Cap::Enter => {
+                self.send(Key::Enter, ctx);
+                if self.auto_capitalization && self.shift == Shift::Off {
+                    self.set_shift(Shift::Once, ctx.get_dirty_region());
+                }
+            }
+        }
+    }
+}
+
+// The uppercase letter of the lowercase ones on the keys, they are all in
+// Latin-1 where the two are 0x20 apart.
+fn upper(c: char) -> char {
+    let code = c as u32;
+    let lowercase = (code >= 'a' as u32 && code <= 'z' as u32) ||
+                    (code >= 0xE0 && code <= 0xFE && code != 0xF7);
+    if lowercase {
+        (code as u8 - 0x20) as char
+    } else {
+        c
+    }
+}
+
+impl Form for Keyboard {
+    fn get_bounding_box(&mut self) -> &mut BoundingBox {
+        &mut self.bounding_box
+    }
+
+    fn set_bounding_box(&mut self, bounding_box: BoundingBox) -> () {
+        self.bounding_box = bounding_box.clone();
+        self.rows.set_bounding_box(bounding_box);
+    }
+
+    fn set_outer_bounding_box(&mut self, bounding_box: BoundingBox) {
+        self.outer_bounding_box = bounding_box;
+    }
+
+    fn get_children<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Form> + 'a> {
+        Box::new(iter::once::<&'a mut Form>(&mut self.rows))
+    }
+
+    fn is_clickable(&mut self) -> Option<&mut Clickable> {
+        Some(self)
+    }
+
+    fn takes_focus(&mut self) -> bool {
+        false
+    }
+
+    fn is_movable(&mut self) -> bool {
+        self.movable
+    }
+
+    fn set_movable(&mut self, value: bool) -> () {
+        self.movable = value;
+    }
+
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box.with_outline());
+    }
+
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        draw_child(target, &self.bounding_box, &self.rows);
+
+        // the pressed key, the popup may reach over the keyboard
+        let (cap, area) = match (self.pressed.as_ref(), self.pressed_area()) {
+            (Some(&(cap, _)), Some(area)) => (cap, area),
+            _ => return,
+        };
+        let style = &self.theme.button;
+        draw_styled_box(target, &area, style, true);
+
+        // the popup shows the character twice as large in its upper half
+        let (text_area, size) = match cap {
+            Cap::Char(_) if self.popups => {
+                (BoundingBox {
+                     height: area.height / 2,
+                     ..area
+                 },
+                 2)
+            }
+            _ => (area, 1),
+        };
+        let mut buffer = [0; 4];
+        TextLayout::new().draw(target,
+                               &text_area,
+                               self.text(cap, &mut buffer),
+                               style.font,
+                               size * style.font_size,
+                               style.text);
+    }
+
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box);
+
+        let outer_if_top = if top {
+            Some(&self.outer_bounding_box)
+        } else {
+            None
+        };
+
+        self.bounding_box
+            .move_in_direction(dir_x, dir_y, outer_if_top);
+        self.rows.set_bounding_box(self.bounding_box.clone());
+
+        dirty.add(&self.bounding_box);
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> () {
+        self.theme = *theme;
+        self.rows.apply_theme(theme);
+    }
+}
+
+impl Clickable for Keyboard {
+    fn handle_event(&mut self, event: &Event, ctx: &mut EventContext) {
+        match *event {
+            Event::Pressed { x, y } => {
+                self.pressed = self.key_at(x, y);
+                if let Some(area) = self.pressed_area() {
+                    ctx.invalidate(&area.with_outline());
+                }
+            }
+            // the key is typed if the finger is still on it
+            Event::Released { x, y } => {
+                if let Some(area) = self.pressed_area() {
+                    ctx.invalidate(&area.with_outline());
+                }
+                let pressed = self.pressed.take();
+                match (pressed, self.key_at(x, y)) {
+                    (Some(pressed), Some(released)) if pressed == released => {
+                        self.type_cap(pressed.0, ctx)
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+    }
+}
@@ -1,25 +1,35 @@
+use collections::String;
 use collections::boxed::Box;
 use core::iter;
-use stm32f7;
-use stm32f7::lcd::Color;
-use stm32f7::lcd::TextWriter;
 
-use draw;
-use draw::draw_pixel_on_text_layer;
+use draw::Color;
+use draw::DrawTarget;
+use font::Font;
+use font::layout::TextLayout;
 use forms::form::Clickable;
 use forms::form::Form;
+use forms::theme::Style;
+use forms::theme::Theme;
 use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
 
 pub struct Label {
     bounding_box: BoundingBox,
     outer_bounding_box: BoundingBox,
     child: Option<Box<Form>>,
     movable: bool,
-    text: &'static str,
+    text: String,
+    // set explicitly, these win over the theme
+    font: Option<&'static Font>,
+    font_size: Option<i32>,
+    color: Option<Color>,
+    // the label style of the theme
+    style: Style,
+    layout: TextLayout,
 }
 
 impl Label {
-    pub fn new(bounding_box: BoundingBox, text: &'static str) -> Label {
+    pub fn new(bounding_box: BoundingBox, text: &str) -> Label {
         let x = bounding_box.x;
         let y = bounding_box.y;
         let width = bounding_box.width;
@@ -35,9 +45,57 @@ impl Label {
             },
             child: None,
             movable: false,
-            text: text,
+            text: String::from(text),
+            font: None,
+            font_size: None,
+            color: None,
+            style: Theme::dark().label,
+            layout: TextLayout::new(),
         }
     }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the text and invalidates the label if it changed.
+    pub fn set_text(&mut self, text: &str, dirty: &mut DirtyRegion) -> () {
+        if self.text == text {
+            return;
+        }
+
+        self.text.clear();
+        self.text.push_str(text);
+        dirty.add(&self.bounding_box);
+    }
+
+    /// Uses `font` instead of the one of the theme.
+    pub fn set_font(&mut self, font: &'static Font, dirty: &mut DirtyRegion) -> () {
+        self.font = Some(font);
+        dirty.add(&self.bounding_box);
+    }
+
+    // Every font pixel is drawn as a square of `size` pixels.
+    pub fn set_font_size(&mut self, size: i32, dirty: &mut DirtyRegion) -> () {
+        if size < 1 {
+            return;
+        }
+
+        self.font_size = Some(size);
+        dirty.add(&self.bounding_box);
+    }
+
+    /// Uses `color` instead of the text color of the theme.
+    pub fn set_color(&mut self, color: Color, dirty: &mut DirtyRegion) -> () {
+        self.color = Some(color);
+        dirty.add(&self.bounding_box);
+    }
+
+    /// Alignment, wrapping and line spacing of the text, one centered line
+    /// by default.
+    pub fn get_text_layout(&mut self) -> &mut TextLayout {
+        &mut self.layout
+    }
 }
 
 impl Form for Label {
@@ -70,36 +128,21 @@ impl Form for Label {
         self.movable = value;
     }
 
-    fn clear(&self) -> () {
-        let offset = 40;
-        for x in self.bounding_box.x - offset..
-                 self.bounding_box.x + self.bounding_box.width + offset {
-            for y in self.bounding_box.y - offset..
-                     self.bounding_box.y + self.bounding_box.height + offset {
-                draw_pixel_on_text_layer(x, y, Color::rgba(0, 0, 0, 0));
-            }
-        }
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box);
     }
 
-    fn draw(&self) -> () {
-        stm32f7::with_stdout(|stdout| {
-            let (width, height) = stdout.width_height(self.text);
-            let (x_center, y_center) = self.bounding_box.get_center();
-
-            let x_offset = x_center - width as i32 / 2;
-            let y_offset = y_center - height as i32 / 2;
-
-            if x_offset < 0 || y_offset < 0 {
-                return;
-            }
-
-            stdout.set_offset(x_offset as usize, y_offset as usize);
-            stdout.print_str(self.text);
-        });
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        self.layout.draw(target,
+                         &self.bounding_box,
+                         &self.text,
+                         self.font.unwrap_or(self.style.font),
+                         self.font_size.unwrap_or(self.style.font_size),
+                         self.color.unwrap_or(self.style.text));
     }
 
-    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool) {
-        self.clear();
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box);
 
         let outer_if_top = if top {
             Some(&self.outer_bounding_box)
@@ -110,6 +153,10 @@ impl Form for Label {
         self.bounding_box
             .move_in_direction(dir_x, dir_y, outer_if_top);
 
-        self.draw();
+        dirty.add(&self.bounding_box);
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> () {
+        self.style = theme.label;
     }
 }
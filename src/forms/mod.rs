@@ -1,3 +1,12 @@
 pub mod button;
+pub mod checkbox;
 pub mod form;
+pub mod image;
+pub mod keyboard;
 pub mod label;
+pub mod progress_bar;
+pub mod radio_group;
+pub mod slider;
+pub mod switch;
+pub mod text_field;
+pub mod theme;
@@ -0,0 +1,201 @@
+use collections::boxed::Box;
+use core::cmp::max;
+use core::cmp::min;
+use core::iter;
+
+use draw::DrawTarget;
+use draw::fill_rectangle;
+use forms::form::Clickable;
+use forms::form::Form;
+use forms::theme::Style;
+use forms::theme::Theme;
+use forms::theme::draw_styled_box;
+use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
+
+// Time the block of an indeterminate bar takes from the left to the right
+// end, in ticks, and its width relative to the bar.
+const SWEEP_TICKS: usize = 1500;
+const BLOCK_FRACTION: i32 = 3;
+
+/// Shows how much of a task is done, filled from the left. A bar that does
+/// not know that is indeterminate, a block then sweeps through it over and
+/// over.
+pub struct ProgressBar {
+    bounding_box: BoundingBox,
+    outer_bounding_box: BoundingBox,
+    movable: bool,
+    value: i32,
+    max: i32,
+    indeterminate: bool,
+    // ticks into the current sweep
+    phase: usize,
+    last_ticks: Option<usize>,
+    style: Style,
+}
+
+impl ProgressBar {
+    pub fn new(bounding_box: BoundingBox, max: i32) -> ProgressBar {
+        assert!(max > 0);
+
+        ProgressBar {
+            bounding_box: bounding_box.clone(),
+            outer_bounding_box: bounding_box,
+            movable: false,
+            value: 0,
+            max: max,
+            indeterminate: false,
+            phase: 0,
+            last_ticks: None,
+            style: Theme::dark().button,
+        }
+    }
+
+    pub fn get_value(&self) -> i32 {
+        self.value
+    }
+
+    /// Sets the progress, clamped to 0 up to the maximum.
+    pub fn set_value(&mut self, value: i32, dirty: &mut DirtyRegion) -> () {
+        let value = max(0, min(value, self.max));
+        if self.value != value {
+            self.value = value;
+            dirty.add(&self.bounding_box.with_outline());
+        }
+    }
+
+    pub fn get_max(&self) -> i32 {
+        self.max
+    }
+
+    pub fn set_max(&mut self, max: i32, dirty: &mut DirtyRegion) -> () {
+        if max <= 0 {
+            return;
+        }
+
+        self.max = max;
+        self.value = min(self.value, max);
+        dirty.add(&self.bounding_box.with_outline());
+    }
+
+    pub fn is_indeterminate(&self) -> bool {
+        self.indeterminate
+    }
+
+    pub fn set_indeterminate(&mut self, indeterminate: bool) -> () {
+        self.indeterminate = indeterminate;
+        self.phase = 0;
+    }
+
+    // The area within the border that is filled.
+    fn inner(&self) -> BoundingBox {
+        let inset = self.style.border_width + 1;
+        BoundingBox {
+            x: self.bounding_box.x + inset,
+            y: self.bounding_box.y + inset,
+            width: self.bounding_box.width - 2 * inset,
+            height: self.bounding_box.height - 2 * inset,
+        }
+    }
+}
+
+impl Form for ProgressBar {
+    fn get_bounding_box(&mut self) -> &mut BoundingBox {
+        &mut self.bounding_box
+    }
+
+    fn set_bounding_box(&mut self, bounding_box: BoundingBox) -> () {
+        self.bounding_box = bounding_box;
+    }
+
+    fn set_outer_bounding_box(&mut self, bounding_box: BoundingBox) {
+        self.outer_bounding_box = bounding_box;
+    }
+
+    fn get_children<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Form> + 'a> {
+        Box::new(iter::empty())
+    }
+
+    fn is_clickable(&mut self) -> Option<&mut Clickable> {
+        None
+    }
+
+    fn is_movable(&mut self) -> bool {
+        self.movable
+    }
+
+    fn set_movable(&mut self, value: bool) -> () {
+        self.movable = value;
+    }
+
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box.with_outline());
+    }
+
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        draw_styled_box(target, &self.bounding_box, &self.style, false);
+
+        let inner = self.inner();
+        if inner.is_empty() {
+            return;
+        }
+
+        let filled = if self.indeterminate {
+            // the block comes in on the left and leaves on the right
+            let width = max(1, inner.width / BLOCK_FRACTION);
+            let distance = inner.width + width;
+            let x = inner.x - width + distance * self.phase as i32 / SWEEP_TICKS as i32;
+            BoundingBox {
+                x: x,
+                width: width,
+                ..inner.clone()
+            }
+        } else {
+            BoundingBox {
+                width: inner.width * self.value / self.max,
+                ..inner.clone()
+            }
+        };
+
+        if let Some(filled) = filled.intersection(&inner) {
+            fill_rectangle(target,
+                           filled.x,
+                           filled.y,
+                           filled.width - 1,
+                           filled.height - 1,
+                           self.style.text);
+        }
+    }
+
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box);
+
+        let outer_if_top = if top {
+            Some(&self.outer_bounding_box)
+        } else {
+            None
+        };
+
+        self.bounding_box
+            .move_in_direction(dir_x, dir_y, outer_if_top);
+
+        dirty.add(&self.bounding_box);
+    }
+
+    fn tick(&mut self, ticks: usize, dirty: &mut DirtyRegion) -> () {
+        let elapsed = match self.last_ticks {
+            Some(last_ticks) => ticks.saturating_sub(last_ticks),
+            None => 0,
+        };
+        self.last_ticks = Some(ticks);
+
+        if self.indeterminate && elapsed > 0 {
+            self.phase = (self.phase + elapsed) % SWEEP_TICKS;
+            dirty.add(&self.inner());
+        }
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> () {
+        self.style = theme.button;
+    }
+}
@@ -0,0 +1,205 @@
+use collections::String;
+use collections::Vec;
+use collections::boxed::Box;
+use core::cmp::min;
+use core::iter;
+
+use action::event::Event;
+use action::event::EventContext;
+use draw::DrawTarget;
+use draw::draw_circle_aa;
+use draw::fill_circle;
+use forms::form::Clickable;
+use forms::form::Form;
+use forms::form::call_action;
+use forms::theme::Style;
+use forms::theme::Theme;
+use forms::theme::draw_styled_text;
+use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
+
+// The circle of an option is as high as its row up to this size, the text
+// starts this far right of it.
+const CIRCLE_SIZE: i32 = 20;
+const TEXT_GAP: i32 = 8;
+
+/// Options below each other of which exactly one is selected. A click
+/// selects the option the finger went down on, even if it is lifted over
+/// another one.
+pub struct RadioGroup {
+    bounding_box: BoundingBox,
+    outer_bounding_box: BoundingBox,
+    movable: bool,
+    options: Vec<String>,
+    selected: usize,
+    // the option a finger is on
+    pressed: Option<usize>,
+    on_change: Option<Box<FnMut(&mut RadioGroup, &mut EventContext)>>,
+    // the circles and the dot in the selected one
+    style: Style,
+    text_style: Style,
+}
+
+impl RadioGroup {
+    pub fn new(bounding_box: BoundingBox, options: &[&str], selected: usize) -> RadioGroup {
+        assert!(selected < options.len());
+
+        let theme = Theme::dark();
+        RadioGroup {
+            bounding_box: bounding_box.clone(),
+            outer_bounding_box: bounding_box,
+            movable: false,
+            options: options.iter().map(|&option| String::from(option)).collect(),
+            selected: selected,
+            pressed: None,
+            on_change: None,
+            style: theme.button,
+            text_style: theme.label,
+        }
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn get_selected_text(&self) -> &str {
+        &self.options[self.selected]
+    }
+
+    /// Selects the option at `index` without calling the change action.
+    pub fn set_selected(&mut self, index: usize, dirty: &mut DirtyRegion) -> () {
+        if index < self.options.len() && index != self.selected {
+            self.selected = index;
+            dirty.add(&self.bounding_box);
+        }
+    }
+
+    /// Calls `callback` after another option was selected by a click.
+    pub fn set_action_on_change<F>(&mut self, callback: F) -> ()
+        where F: FnMut(&mut RadioGroup, &mut EventContext) + 'static
+    {
+        self.on_change = Some(Box::new(callback));
+    }
+
+    // The rows share the height of the group equally.
+    fn row(&self, index: usize) -> BoundingBox {
+        let count = self.options.len() as i32;
+        let top = self.bounding_box.height * index as i32 / count;
+        let bottom = self.bounding_box.height * (index as i32 + 1) / count;
+        BoundingBox {
+            x: self.bounding_box.x,
+            y: self.bounding_box.y + top,
+            width: self.bounding_box.width,
+            height: bottom - top,
+        }
+    }
+
+    // The option at the screen position, None outside of the group.
+    fn option_at(&self, x: i32, y: i32) -> Option<usize> {
+        (0..self.options.len()).find(|&index| self.row(index).is_in_bound(x, y))
+    }
+}
+
+impl Form for RadioGroup {
+    fn get_bounding_box(&mut self) -> &mut BoundingBox {
+        &mut self.bounding_box
+    }
+
+    fn set_bounding_box(&mut self, bounding_box: BoundingBox) -> () {
+        self.bounding_box = bounding_box;
+    }
+
+    fn set_outer_bounding_box(&mut self, bounding_box: BoundingBox) {
+        self.outer_bounding_box = bounding_box;
+    }
+
+    fn get_children<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Form> + 'a> {
+        Box::new(iter::empty())
+    }
+
+    fn is_clickable(&mut self) -> Option<&mut Clickable> {
+        Some(self)
+    }
+
+    fn is_movable(&mut self) -> bool {
+        self.movable
+    }
+
+    fn set_movable(&mut self, value: bool) -> () {
+        self.movable = value;
+    }
+
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box.with_outline());
+    }
+
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        for (index, option) in self.options.iter().enumerate() {
+            let row = self.row(index);
+            let radius = min(row.height, CIRCLE_SIZE) / 2;
+            let (cx, cy) = (row.x + radius, row.y + row.height / 2);
+
+            if self.pressed == Some(index) && self.style.pressed.alpha != 0 {
+                fill_circle(target, cx, cy, radius, self.style.pressed);
+            } else if self.style.background.alpha != 0 {
+                fill_circle(target, cx, cy, radius, self.style.background);
+            }
+            draw_circle_aa(target, cx, cy, radius, self.style.border);
+            if index == self.selected {
+                fill_circle(target, cx, cy, radius / 2, self.style.text);
+            }
+
+            let inset = 2 * radius + TEXT_GAP;
+            let text_area = BoundingBox {
+                x: row.x + inset,
+                width: row.width - inset,
+                ..row
+            };
+            draw_styled_text(target, &text_area, option, &self.text_style);
+        }
+    }
+
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box);
+
+        let outer_if_top = if top {
+            Some(&self.outer_bounding_box)
+        } else {
+            None
+        };
+
+        self.bounding_box
+            .move_in_direction(dir_x, dir_y, outer_if_top);
+
+        dirty.add(&self.bounding_box);
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> () {
+        self.style = theme.button;
+        self.text_style = theme.label;
+    }
+}
+
+impl Clickable for RadioGroup {
+    fn handle_event(&mut self, event: &Event, ctx: &mut EventContext) {
+        match *event {
+            Event::Pressed { x, y } => self.pressed = self.option_at(x, y),
+            Event::Released { x, y } => {
+                let pressed = self.pressed.take();
+                let inside = self.bounding_box.is_in_bound(x, y);
+                match pressed {
+                    Some(index) if inside && index != self.selected => self.selected = index,
+                    _ => {
+                        ctx.invalidate(&self.bounding_box);
+                        return;
+                    }
+                }
+
+                call_action(self, |radio| &mut radio.on_change, |action, radio| action(radio, ctx));
+            }
+            _ => return,
+        }
+
+        ctx.invalidate(&self.bounding_box);
+    }
+}
@@ -0,0 +1,302 @@
+use collections::boxed::Box;
+use core::cmp::max;
+use core::cmp::min;
+use core::iter;
+
+use action::event::Event;
+use action::event::EventContext;
+use draw::DrawTarget;
+use draw::fill_circle;
+use draw::fill_rounded_rectangle;
+use forms::form::Clickable;
+use forms::form::Form;
+use forms::form::call_action;
+use forms::theme::Style;
+use forms::theme::Theme;
+use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
+
+// Thickness of the track and the largest radius of the knob.
+const TRACK_WIDTH: i32 = 4;
+const KNOB_RADIUS: i32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    // the minimum is on the left
+    Horizontal,
+    // the minimum is at the bottom
+    Vertical,
+}
+
+/// Picks a value between a minimum and a maximum with a knob that follows
+/// the finger. Pressing the track moves the knob there right away.
+pub struct Slider {
+    bounding_box: BoundingBox,
+    outer_bounding_box: BoundingBox,
+    movable: bool,
+    min: i32,
+    max: i32,
+    // the values the knob snaps to are `min` plus multiples of this
+    step: i32,
+    value: i32,
+    orientation: Orientation,
+    pressed: bool,
+    on_change: Option<Box<FnMut(&mut Slider, &mut EventContext)>>,
+    style: Style,
+}
+
+impl Slider {
+    pub fn new(bounding_box: BoundingBox, min: i32, max: i32, value: i32) -> Slider {
+        assert!(min <= max);
+
+        Slider {
+            bounding_box: bounding_box.clone(),
+            outer_bounding_box: bounding_box,
+            movable: false,
+            min: min,
+            max: max,
+            step: 1,
+            value: clamp(value, min, max),
+            orientation: Orientation::Horizontal,
+            pressed: false,
+            on_change: None,
+            style: Theme::dark().button,
+        }
+    }
+
+    /// Snaps the values to `min` plus multiples of `step`, for setting up a
+    /// new slider. `set_step` changes it later on.
+    pub fn with_step(mut self, step: i32) -> Slider {
+        if step >= 1 {
+            self.step = step;
+            self.value = self.snap(self.value);
+        }
+        self
+    }
+
+    pub fn get_value(&self) -> i32 {
+        self.value
+    }
+
+    /// Moves the knob to `value`, snapped to the steps and clamped to the
+    /// range, without calling the change action.
+    pub fn set_value(&mut self, value: i32, dirty: &mut DirtyRegion) -> () {
+        let value = self.snap(value);
+        if self.value != value {
+            self.value = value;
+            dirty.add(&self.bounding_box.with_outline());
+        }
+    }
+
+    pub fn get_range(&self) -> (i32, i32) {
+        (self.min, self.max)
+    }
+
+    pub fn set_range(&mut self, min: i32, max: i32, dirty: &mut DirtyRegion) -> () {
+        if min > max {
+            return;
+        }
+
+        self.min = min;
+        self.max = max;
+        self.value = self.snap(self.value);
+        dirty.add(&self.bounding_box.with_outline());
+    }
+
+    pub fn set_step(&mut self, step: i32, dirty: &mut DirtyRegion) -> () {
+        if step < 1 {
+            return;
+        }
+
+        self.step = step;
+        self.value = self.snap(self.value);
+        dirty.add(&self.bounding_box.with_outline());
+    }
+
+    pub fn set_orientation(&mut self, orientation: Orientation, dirty: &mut DirtyRegion) -> () {
+        if self.orientation != orientation {
+            self.orientation = orientation;
+            dirty.add(&self.bounding_box.with_outline());
+        }
+    }
+
+    /// Calls `callback` whenever the finger moved the knob to another value.
+    pub fn set_action_on_change<F>(&mut self, callback: F) -> ()
+        where F: FnMut(&mut Slider, &mut EventContext) + 'static
+    {
+        self.on_change = Some(Box::new(callback));
+    }
+
+    // The nearest step in the range.
+    fn snap(&self, value: i32) -> i32 {
+        let steps = (clamp(value, self.min, self.max) - self.min + self.step / 2) / self.step;
+        min(self.min + steps * self.step, self.max)
+    }
+
+    fn knob_radius(&self) -> i32 {
+        let thickness = match self.orientation {
+            Orientation::Horizontal => self.bounding_box.height,
+            Orientation::Vertical => self.bounding_box.width,
+        };
+        max(1, min(KNOB_RADIUS, thickness / 2))
+    }
+
+    // The distance the knob center can move and its offset from the minimum
+    // end for the current value.
+    fn travel(&self) -> (i32, i32) {
+        let length = match self.orientation {
+            Orientation::Horizontal => self.bounding_box.width,
+            Orientation::Vertical => self.bounding_box.height,
+        };
+        let travel = max(0, length - 2 * self.knob_radius());
+        let range = self.max - self.min;
+        let offset = if range == 0 {
+            0
+        } else {
+            travel * (self.value - self.min) / range
+        };
+        (travel, offset)
+    }
+
+    // The value for the knob centered at the screen position.
+    fn value_at(&self, x: i32, y: i32) -> i32 {
+        let (travel, _) = self.travel();
+        let radius = self.knob_radius();
+        let offset = match self.orientation {
+            Orientation::Horizontal => x - self.bounding_box.x - radius,
+            Orientation::Vertical => self.bounding_box.y + self.bounding_box.height - radius - y,
+        };
+        if travel == 0 {
+            return self.min;
+        }
+
+        let range = self.max - self.min;
+        let offset = clamp(offset, 0, travel);
+        self.snap(self.min + (offset * range + travel / 2) / travel)
+    }
+
+    // Follows the finger and calls the change action for a new value.
+    fn drag_to(&mut self, x: i32, y: i32, ctx: &mut EventContext) {
+        let value = self.value_at(x, y);
+        if value == self.value {
+            return;
+        }
+        self.value = value;
+
+        call_action(self, |slider| &mut slider.on_change, |action, slider| action(slider, ctx));
+    }
+}
+
+fn clamp(value: i32, low: i32, high: i32) -> i32 {
+    max(low, min(value, high))
+}
+
+impl Form for Slider {
+    fn get_bounding_box(&mut self) -> &mut BoundingBox {
+        &mut self.bounding_box
+    }
+
+    fn set_bounding_box(&mut self, bounding_box: BoundingBox) -> () {
+        self.bounding_box = bounding_box;
+    }
+
+    fn set_outer_bounding_box(&mut self, bounding_box: BoundingBox) {
+        self.outer_bounding_box = bounding_box;
+    }
+
+    fn get_children<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Form> + 'a> {
+        Box::new(iter::empty())
+    }
+
+    fn is_clickable(&mut self) -> Option<&mut Clickable> {
+        Some(self)
+    }
+
+    fn is_movable(&mut self) -> bool {
+        self.movable
+    }
+
+    fn set_movable(&mut self, value: bool) -> () {
+        self.movable = value;
+    }
+
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box.with_outline());
+    }
+
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        let area = &self.bounding_box;
+        let radius = self.knob_radius();
+        let (travel, offset) = self.travel();
+
+        // the part of the track up to the knob is filled with the border
+        // color, the rest with the pressed color
+        let (knob_x, knob_y, full, empty) = match self.orientation {
+            Orientation::Horizontal => {
+                let y = area.y + area.height / 2 - TRACK_WIDTH / 2;
+                let x = area.x + radius;
+                (x + offset,
+                 area.y + area.height / 2,
+                 (x, y, offset, TRACK_WIDTH),
+                 (x + offset, y, travel - offset, TRACK_WIDTH))
+            }
+            Orientation::Vertical => {
+                let x = area.x + area.width / 2 - TRACK_WIDTH / 2;
+                let bottom = area.y + area.height - radius;
+                (area.x + area.width / 2,
+                 bottom - offset,
+                 (x, bottom - offset, TRACK_WIDTH, offset),
+                 (x, bottom - travel, TRACK_WIDTH, travel - offset))
+            }
+        };
+
+        let (x, y, width, height) = empty;
+        fill_rounded_rectangle(target, x, y, width, height, TRACK_WIDTH / 2, self.style.pressed);
+        let (x, y, width, height) = full;
+        fill_rounded_rectangle(target, x, y, width, height, TRACK_WIDTH / 2, self.style.border);
+
+        // the knob is hollow while a finger is on it
+        fill_circle(target, knob_x, knob_y, radius, self.style.border);
+        if self.pressed {
+            fill_circle(target, knob_x, knob_y, radius / 2, self.style.pressed);
+        }
+    }
+
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box.with_outline());
+
+        let outer_if_top = if top {
+            Some(&self.outer_bounding_box)
+        } else {
+            None
+        };
+
+        self.bounding_box
+            .move_in_direction(dir_x, dir_y, outer_if_top);
+
+        dirty.add(&self.bounding_box.with_outline());
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> () {
+        self.style = theme.button;
+    }
+}
+
+impl Clickable for Slider {
+    fn handle_event(&mut self, event: &Event, ctx: &mut EventContext) {
+        match *event {
+            Event::Pressed { x, y } => {
+                self.pressed = true;
+                self.drag_to(x, y, ctx);
+            }
+            Event::Moved { x, y } => self.drag_to(x, y, ctx),
+            Event::Released { .. } => self.pressed = false,
+            _ => return,
+        }
+
+        // the finger belongs to the slider, scroll views around it do not
+        // move with it
+        ctx.stop_propagation();
+        ctx.invalidate(&self.bounding_box.with_outline());
+    }
+}
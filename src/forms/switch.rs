@@ -0,0 +1,227 @@
+use collections::String;
+use collections::boxed::Box;
+use core::cmp::max;
+use core::cmp::min;
+use core::iter;
+
+use action::event::Event;
+use action::event::EventContext;
+use draw::Color;
+use draw::DrawTarget;
+use draw::draw_rounded_rectangle;
+use draw::fill_circle;
+use draw::fill_rounded_rectangle;
+use forms::form::Clickable;
+use forms::form::Form;
+use forms::form::call_action;
+use forms::theme::Style;
+use forms::theme::Theme;
+use forms::theme::draw_styled_text;
+use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
+
+// The track on the right is twice as wide as high, at most this high.
+const TRACK_HEIGHT: i32 = 20;
+// Time the knob takes from one end of the track to the other, in ticks.
+const SLIDE_TICKS: i32 = 150;
+
+/// An on/off switch with its text on the left and a track on the right, the
+/// knob slides to the other end when it is clicked.
+pub struct Switch {
+    bounding_box: BoundingBox,
+    outer_bounding_box: BoundingBox,
+    movable: bool,
+    text: String,
+    on: bool,
+    // how far the knob is towards the on end, 0 to SLIDE_TICKS
+    position: i32,
+    last_ticks: Option<usize>,
+    on_change: Option<Box<FnMut(&mut Switch, &mut EventContext)>>,
+    // the track and the knob
+    style: Style,
+    text_style: Style,
+}
+
+impl Switch {
+    pub fn new(bounding_box: BoundingBox, text: &str, on: bool) -> Switch {
+        let theme = Theme::dark();
+        Switch {
+            bounding_box: bounding_box.clone(),
+            outer_bounding_box: bounding_box,
+            movable: false,
+            text: String::from(text),
+            on: on,
+            position: if on { SLIDE_TICKS } else { 0 },
+            last_ticks: None,
+            on_change: None,
+            style: theme.button,
+            text_style: theme.label,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    /// Switches without calling the change action, the knob slides over
+    /// with the next ticks.
+    pub fn set_on(&mut self, on: bool, dirty: &mut DirtyRegion) -> () {
+        if self.on != on {
+            self.on = on;
+            dirty.add(&self.bounding_box);
+        }
+    }
+
+    /// True while the knob is on its way to the other end.
+    pub fn is_sliding(&self) -> bool {
+        self.position != if self.on { SLIDE_TICKS } else { 0 }
+    }
+
+    /// Calls `callback` after the switch was turned on or off by a click.
+    pub fn set_action_on_change<F>(&mut self, callback: F) -> ()
+        where F: FnMut(&mut Switch, &mut EventContext) + 'static
+    {
+        self.on_change = Some(Box::new(callback));
+    }
+
+    // The track at the right end, vertically centered.
+    fn track_area(&self) -> BoundingBox {
+        let height = min(self.bounding_box.height, TRACK_HEIGHT);
+        let width = min(self.bounding_box.width, 2 * height);
+        BoundingBox {
+            x: self.bounding_box.x + self.bounding_box.width - width,
+            y: self.bounding_box.y + (self.bounding_box.height - height) / 2,
+            width: width,
+            height: height,
+        }
+    }
+}
+
+impl Form for Switch {
+    fn get_bounding_box(&mut self) -> &mut BoundingBox {
+        &mut self.bounding_box
+    }
+
+    fn set_bounding_box(&mut self, bounding_box: BoundingBox) -> () {
+        self.bounding_box = bounding_box;
+    }
+
+    fn set_outer_bounding_box(&mut self, bounding_box: BoundingBox) {
+        self.outer_bounding_box = bounding_box;
+    }
+
+    fn get_children<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Form> + 'a> {
+        Box::new(iter::empty())
+    }
+
+    fn is_clickable(&mut self) -> Option<&mut Clickable> {
+        Some(self)
+    }
+
+    fn is_movable(&mut self) -> bool {
+        self.movable
+    }
+
+    fn set_movable(&mut self, value: bool) -> () {
+        self.movable = value;
+    }
+
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box.with_outline());
+    }
+
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        let track = self.track_area();
+        let radius = track.height / 2;
+
+        // the track is filled with the border color once the knob is past
+        // the middle, the knob takes the background color then
+        let on = self.position * 2 > SLIDE_TICKS;
+        let background = if self.style.background.alpha == 0 {
+            Color::rgb(0, 0, 0)
+        } else {
+            self.style.background
+        };
+        let (fill, knob) = if on {
+            (self.style.border, background)
+        } else {
+            (background, self.style.border)
+        };
+        fill_rounded_rectangle(target,
+                               track.x,
+                               track.y,
+                               track.width,
+                               track.height,
+                               radius,
+                               fill);
+        draw_rounded_rectangle(target,
+                               track.x,
+                               track.y,
+                               track.width,
+                               track.height,
+                               radius,
+                               self.style.border);
+
+        let travel = track.width - track.height;
+        fill_circle(target,
+                    track.x + radius + travel * self.position / SLIDE_TICKS,
+                    track.y + radius,
+                    radius - 3,
+                    knob);
+
+        let text_area = BoundingBox {
+            width: track.x - self.bounding_box.x,
+            ..self.bounding_box.clone()
+        };
+        draw_styled_text(target, &text_area, &self.text, &self.text_style);
+    }
+
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box);
+
+        let outer_if_top = if top {
+            Some(&self.outer_bounding_box)
+        } else {
+            None
+        };
+
+        self.bounding_box
+            .move_in_direction(dir_x, dir_y, outer_if_top);
+
+        dirty.add(&self.bounding_box);
+    }
+
+    fn tick(&mut self, ticks: usize, dirty: &mut DirtyRegion) -> () {
+        let elapsed = match self.last_ticks {
+            Some(last_ticks) => ticks.saturating_sub(last_ticks) as i32,
+            None => 0,
+        };
+        self.last_ticks = Some(ticks);
+
+        if self.is_sliding() && elapsed > 0 {
+            self.position = if self.on {
+                min(self.position + elapsed, SLIDE_TICKS)
+            } else {
+                max(self.position - elapsed, 0)
+            };
+            dirty.add(&self.track_area().with_outline());
+        }
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> () {
+        self.style = theme.button;
+        self.text_style = theme.label;
+    }
+}
+
+impl Clickable for Switch {
+    fn handle_event(&mut self, event: &Event, ctx: &mut EventContext) {
+        if let Event::Clicked { .. } = *event {
+            self.on = !self.on;
+
+            call_action(self, |switch| &mut switch.on_change, |action, switch| action(switch, ctx));
+
+            ctx.invalidate(&self.bounding_box);
+        }
+    }
+}
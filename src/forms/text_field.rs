@@ -0,0 +1,266 @@
+use collections::String;
+use collections::boxed::Box;
+use core::iter;
+
+use action::event::Event;
+use action::event::EventContext;
+use action::event::Key;
+use draw::DrawTarget;
+use draw::fill_rectangle;
+use font::text_size;
+use forms::form::Clickable;
+use forms::form::Form;
+use forms::form::call_action;
+use forms::theme::Style;
+use forms::theme::Theme;
+use forms::theme::draw_styled_box;
+use forms::theme::draw_styled_text;
+use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
+
+// The caret is shown and hidden for this many ticks each.
+const BLINK_TICKS: usize = 500;
+
+/// One line of text that is typed into with the keys sent to it while it
+/// is focused, e.g. from the on-screen keyboard. Pressing the field focuses
+/// it.
+pub struct TextField {
+    bounding_box: BoundingBox,
+    outer_bounding_box: BoundingBox,
+    movable: bool,
+    text: String,
+    // shown while the text is empty
+    placeholder: String,
+    max_length: Option<usize>,
+    focused: bool,
+    caret_visible: bool,
+    // ticks at which the caret was last shown or hidden
+    blink_ticks: Option<usize>,
+    on_change: Option<Box<FnMut(&mut TextField, &mut EventContext)>>,
+    on_enter: Option<Box<FnMut(&mut TextField, &mut EventContext)>>,
+    style: Style,
+}
+
+impl TextField {
+    pub fn new(bounding_box: BoundingBox) -> TextField {
+        TextField {
+            bounding_box: bounding_box.clone(),
+            outer_bounding_box: bounding_box,
+            movable: false,
+            text: String::new(),
+            placeholder: String::new(),
+            max_length: None,
+            focused: false,
+            caret_visible: false,
+            blink_ticks: None,
+            on_change: None,
+            on_enter: None,
+            style: Theme::dark().button,
+        }
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the text without calling the change action.
+    pub fn set_text(&mut self, text: &str, dirty: &mut DirtyRegion) -> () {
+        if self.text == text {
+            return;
+        }
+
+        self.text.clear();
+        self.text.push_str(text);
+        dirty.add(&self.bounding_box.with_outline());
+    }
+
+    pub fn set_placeholder(&mut self, placeholder: &str) -> () {
+        self.placeholder.clear();
+        self.placeholder.push_str(placeholder);
+    }
+
+    /// Ignores typed characters once the text has `max_length` of them.
+    pub fn set_max_length(&mut self, max_length: Option<usize>) -> () {
+        self.max_length = max_length;
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Calls `callback` after a key changed the text.
+    pub fn set_action_on_change<F>(&mut self, callback: F) -> ()
+        where F: FnMut(&mut TextField, &mut EventContext) + 'static
+    {
+        self.on_change = Some(Box::new(callback));
+    }
+
+    /// Calls `callback` when the enter key is sent to the field.
+    pub fn set_action_on_enter<F>(&mut self, callback: F) -> ()
+        where F: FnMut(&mut TextField, &mut EventContext) + 'static
+    {
+        self.on_enter = Some(Box::new(callback));
+    }
+
+    // Applies `key` to the text, returns if it changed.
+    fn type_key(&mut self, key: Key) -> bool {
+        match key {
+            Key::Char(c) => {
+                let full = match self.max_length {
+                    Some(max_length) => self.text.chars().count() >= max_length,
+                    None => false,
+                };
+                if !full {
+                    self.text.push(c);
+                }
+                !full
+            }
+            Key::Backspace => self.text.pop().is_some(),
+            Key::Enter => false,
+        }
+    }
+
+    // The end of the text that fits into `width`, the caret follows the
+    // last character.
+    fn visible_text(&self, width: i32) -> &str {
+        for (index, _) in self.text.char_indices() {
+            let text = &self.text[index..];
+            let (text_width, _) = text_size(self.style.font, self.style.font_size, text);
+            if text_width <= width {
+                return text;
+            }
+        }
+        ""
+    }
+}
+
+impl Form for TextField {
+    fn get_bounding_box(&mut self) -> &mut BoundingBox {
+        &mut self.bounding_box
+    }
+
+    fn set_bounding_box(&mut self, bounding_box: BoundingBox) -> () {
+        self.bounding_box = bounding_box;
+    }
+
+    fn set_outer_bounding_box(&mut self, bounding_box: BoundingBox) {
+        self.outer_bounding_box = bounding_box;
+    }
+
+    fn get_children<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Form> + 'a> {
+        Box::new(iter::empty())
+    }
+
+    fn is_clickable(&mut self) -> Option<&mut Clickable> {
+        Some(self)
+    }
+
+    fn is_movable(&mut self) -> bool {
+        self.movable
+    }
+
+    fn set_movable(&mut self, value: bool) -> () {
+        self.movable = value;
+    }
+
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box.with_outline());
+    }
+
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        draw_styled_box(target, &self.bounding_box, &self.style, false);
+
+        let content = self.style.content_box(&self.bounding_box);
+        let area = BoundingBox {
+            x: content.x + 2,
+            width: content.width - 4,
+            ..content
+        };
+        let caret_width = 2;
+
+        if self.text.is_empty() {
+            let style = Style { text: self.style.pressed, ..self.style };
+            draw_styled_text(target, &area, &self.placeholder, &style);
+        }
+        let text = self.visible_text(area.width - caret_width);
+        draw_styled_text(target, &area, text, &self.style);
+
+        if self.focused && self.caret_visible {
+            let (width, _) = text_size(self.style.font, self.style.font_size, text);
+            let height = self.style.font.line_height() * self.style.font_size;
+            fill_rectangle(target,
+                           area.x + width,
+                           area.y + (area.height - height) / 2,
+                           caret_width - 1,
+                           height - 1,
+                           self.style.text);
+        }
+    }
+
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box);
+
+        let outer_if_top = if top {
+            Some(&self.outer_bounding_box)
+        } else {
+            None
+        };
+
+        self.bounding_box
+            .move_in_direction(dir_x, dir_y, outer_if_top);
+
+        dirty.add(&self.bounding_box);
+    }
+
+    fn tick(&mut self, ticks: usize, dirty: &mut DirtyRegion) -> () {
+        if !self.focused {
+            return;
+        }
+
+        let blink_ticks = match self.blink_ticks {
+            Some(blink_ticks) => blink_ticks,
+            None => {
+                self.blink_ticks = Some(ticks);
+                return;
+            }
+        };
+        if ticks.saturating_sub(blink_ticks) >= BLINK_TICKS {
+            self.caret_visible = !self.caret_visible;
+            self.blink_ticks = Some(ticks);
+            dirty.add(&self.bounding_box.with_outline());
+        }
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> () {
+        self.style = theme.button;
+    }
+}
+
+impl Clickable for TextField {
+    fn handle_event(&mut self, event: &Event, ctx: &mut EventContext) {
+        match *event {
+            Event::FocusGained => {
+                self.focused = true;
+                self.caret_visible = true;
+                self.blink_ticks = None;
+            }
+            Event::FocusLost => self.focused = false,
+            Event::Key(Key::Enter) => {
+                call_action(self, |field| &mut field.on_enter, |action, field| action(field, ctx));
+            }
+            Event::Key(key) => {
+                if !self.type_key(key) {
+                    return;
+                }
+
+                // the caret stays while typing
+                self.caret_visible = true;
+                self.blink_ticks = None;
+                call_action(self, |field| &mut field.on_change, |action, field| action(field, ctx));
+            }
+            _ => return,
+        }
+
+        ctx.invalidate(&self.bounding_box.with_outline());
+    }
+}
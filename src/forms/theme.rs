@@ -0,0 +1,164 @@
+//! Colors and measures of the forms.
+//!
+//! A `Theme` is handed down the form tree with `Form::apply_theme`, every
+//! form takes the style it needs and passes the theme on to its children.
+//! The `App` applies its theme to every view it shows, so it can be switched
+//! at runtime.
+
+use draw::Color;
+use draw::DrawTarget;
+use draw::draw_rounded_rectangle;
+use draw::draw_thick_rectangle;
+use draw::fill_rectangle;
+use draw::fill_rounded_rectangle;
+use font;
+use font::Font;
+use font::layout::HorizontalAlign;
+use font::layout::TextLayout;
+use util::bounding_box::BoundingBox;
+
+/// The look of one kind of form.
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    // transparent colors are not drawn
+    pub background: Color,
+    pub border: Color,
+    // fills the form while it is pressed
+    pub pressed: Color,
+    pub text: Color,
+    pub font: &'static Font,
+    // every font pixel is drawn as a square of this size
+    pub font_size: i32,
+    pub border_width: i32,
+    // space between the border and the child
+    pub padding: i32,
+    pub corner_radius: i32,
+}
+
+impl Style {
+    /// The content area of a form with this style in `bounding_box`.
+    pub fn content_box(&self, bounding_box: &BoundingBox) -> BoundingBox {
+        let inset = self.padding + self.border_width;
+        BoundingBox {
+            x: bounding_box.x + inset,
+            y: bounding_box.y + inset,
+            width: bounding_box.width - 2 * inset,
+            height: bounding_box.height - 2 * inset,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub name: &'static str,
+    // behind the whole view
+    pub background: Color,
+    pub button: Style,
+    pub label: Style,
+    // containers with a border, like the move box
+    pub frame: Style,
+}
+
+fn transparent() -> Color {
+    Color::rgba(0, 0, 0, 0)
+}
+
+impl Theme {
+    /// White lines on the black LTDC background, the default.
+    pub fn dark() -> Theme {
+        let button = Style {
+            background: transparent(),
+            border: Color::from_hex(0xFFFFFF),
+            pressed: Color::from_hex(0x404040),
+            text: Color::from_hex(0xFFFFFF),
+            font: &font::BUILTIN,
+            font_size: 1,
+            border_width: 2,
+            padding: 0,
+            corner_radius: 0,
+        };
+
+        Theme {
+            name: "Standard Dark",
+            background: transparent(),
+            button: button,
+            label: button,
+            frame: Style {
+                border_width: 1,
+                pressed: transparent(),
+                ..button
+            },
+        }
+    }
+
+    pub fn light() -> Theme {
+        let button = Style {
+            background: Color::from_hex(0xFFFFFF),
+            border: Color::from_hex(0x303030),
+            pressed: Color::from_hex(0xC8C8C8),
+            text: Color::from_hex(0x000000),
+            font: &font::BUILTIN,
+            font_size: 1,
+            border_width: 1,
+            padding: 4,
+            corner_radius: 6,
+        };
+
+        Theme {
+            name: "Standard Light",
+            background: Color::from_hex(0xF0F0F0),
+            button: button,
+            label: button,
+            frame: Style {
+                background: transparent(),
+                pressed: transparent(),
+                corner_radius: 0,
+                ..button
+            },
+        }
+    }
+}
+
+/// Draws the background, the pressed state and the border of `style` in
+/// `bounding_box`.
+pub fn draw_styled_box(target: &mut DrawTarget,
+                       bounding_box: &BoundingBox,
+                       style: &Style,
+                       pressed: bool) {
+    let (x, y) = (bounding_box.x, bounding_box.y);
+    let (width, height) = (bounding_box.width, bounding_box.height);
+    let radius = style.corner_radius;
+
+    let fill = if pressed { style.pressed } else { style.background };
+    if fill.alpha != 0 {
+        if radius > 0 {
+            fill_rounded_rectangle(target, x, y, width, height, radius, fill);
+        } else {
+            fill_rectangle(target, x, y, width, height, fill);
+        }
+    }
+
+    if radius > 0 {
+        // rings of shrinking radius towards the inside
+        for i in 0..style.border_width {
+            draw_rounded_rectangle(target,
+                                   x + i,
+                                   y + i,
+                                   width - 2 * i,
+                                   height - 2 * i,
+                                   radius - i,
+                                   style.border);
+        }
+    } else {
+        draw_thick_rectangle(target, x, y, width, height, style.border_width, style.border);
+    }
+}
+
+/// Draws one line of `text` left aligned and vertically centered in `area`
+/// with the font and text color of `style`, e.g. next to the box of a
+/// checkbox.
+pub fn draw_styled_text(target: &mut DrawTarget, area: &BoundingBox, text: &str, style: &Style) {
+    let mut layout = TextLayout::new();
+    layout.horizontal = HorizontalAlign::Left;
+    layout.draw(target, area, text, style.font, style.font_size, style.text);
+}
@@ -0,0 +1,148 @@
+//! Uncompressed Windows bitmaps with 8 bit palettes, 24 or 32 bits per
+//! pixel.
+
+use collections::Vec;
+
+use draw::Color;
+use image::Bitmap;
+use image::DecodeError;
+
+// compression types, bit fields are only accepted with 32 bits per pixel
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+
+// larger bitmaps would not fit into the memory of the board anyway
+const MAX_SIZE: i32 = 0x7FFF;
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16, DecodeError> {
+    match data.get(offset..offset + 2) {
+        Some(bytes) => Ok(bytes[0] as u16 | (bytes[1] as u16) << 8),
+        None => Err(DecodeError::Truncated),
+    }
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, DecodeError> {
+    let low = u16_at(data, offset)? as u32;
+    let high = u16_at(data, offset + 2)? as u32;
+    Ok(low | high << 16)
+}
+
+pub fn decode(data: &[u8]) -> Result<Bitmap, DecodeError> {
+    if data.len() < 2 || &data[..2] != b"BM" {
+        return Err(DecodeError::InvalidHeader);
+    }
+
+    let pixel_offset = u32_at(data, 10)? as usize;
+    let header_size = u32_at(data, 14)? as usize;
+    let width = u32_at(data, 18)? as i32;
+    let height = u32_at(data, 22)? as i32;
+    let bits = u16_at(data, 28)?;
+    let compression = u32_at(data, 30)?;
+    if width <= 0 || width > MAX_SIZE || height == 0 {
+        return Err(DecodeError::InvalidHeader);
+    }
+
+    match (bits, compression) {
+        (8, BI_RGB) | (24, BI_RGB) | (32, BI_RGB) | (32, BI_BITFIELDS) => {}
+        _ => return Err(DecodeError::Unsupported),
+    }
+
+    // the palette follows the header, in blue, green, red and one unused byte
+    let mut palette = Vec::new();
+    if bits == 8 {
+        let count = match u32_at(data, 46)? {
+            0 => 256,
+            count => count as usize,
+        };
+        for i in 0..count {
+            let entry = 14 + header_size + i * 4;
+            match data.get(entry..entry + 3) {
+                Some(bgr) => palette.push(Color::rgb(bgr[2], bgr[1], bgr[0])),
+                None => return Err(DecodeError::Truncated),
+            }
+        }
+    }
+
+    // rows are stored bottom up unless the height is negative, each is
+    // padded to a multiple of 4 bytes
+    let bottom_up = height > 0;
+    let height = match height.checked_abs() {
+        Some(height) if height <= MAX_SIZE => height,
+        _ => return Err(DecodeError::InvalidHeader),
+    };
+    let stride = ((width * bits as i32 + 31) / 32 * 4) as usize;
+    let bytes = bits as usize / 8;
+
+    // all rows have to be in the data before the pixels are allocated
+    let end = stride.checked_mul(height as usize - 1)
+        .and_then(|size| size.checked_add(pixel_offset))
+        .and_then(|size| size.checked_add(width as usize * bytes));
+    match end {
+        Some(end) if end <= data.len() => {}
+        _ => return Err(DecodeError::Truncated),
+    }
+
+    let mut pixels = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        let row = if bottom_up { height - 1 - y } else { y } as usize;
+        let start = pixel_offset + row * stride;
+        let row = match data.get(start..start + width as usize * bytes) {
+            Some(row) => row,
+            None => return Err(DecodeError::Truncated),
+        };
+
+        for pixel in row.chunks(bytes) {
+            let color = match bits {
+                8 => palette.get(pixel[0] as usize).cloned().unwrap_or(Color::rgba(0, 0, 0, 0)),
+                24 => Color::rgb(pixel[2], pixel[1], pixel[0]),
+                // only the bit fields variant has a meaningful alpha channel
+                _ if compression == BI_BITFIELDS => {
+                    Color::rgba(pixel[2], pixel[1], pixel[0], pixel[3])
+                }
+                _ => Color::rgb(pixel[2], pixel[1], pixel[0]),
+            };
+            pixels.push(color);
+        }
+    }
+
+    Ok(Bitmap::new(width, height, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2x2 pixels with 24 bits, bottom up and rows padded to 8 bytes
+    fn small() -> Vec<u8> {
+        let mut bmp = b"BM".to_vec();
+        bmp.extend(&[0; 8]);
+        bmp.extend(&[54, 0, 0, 0, 40, 0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0, 1, 0, 24, 0]);
+        bmp.extend(&[0; 24]);
+        bmp.extend(&[255, 255, 255, 0, 0, 255, 0, 0]);
+        bmp.extend(&[0, 0, 255, 255, 0, 0, 0, 0]);
+        bmp
+    }
+
+    #[test]
+    fn pixels() {
+        let bitmap = decode(&small()).unwrap();
+        assert_eq!((bitmap.width(), bitmap.height()), (2, 2));
+        assert_eq!(bitmap.get_pixel(0, 0), Color::rgb(255, 0, 0));
+        assert_eq!(bitmap.get_pixel(1, 0), Color::rgb(0, 0, 255));
+        assert_eq!(bitmap.get_pixel(0, 1), Color::rgb(255, 255, 255));
+    }
+
+    #[test]
+    fn invalid() {
+        let bmp = small();
+        assert_eq!(decode(&bmp[..60]).err(), Some(DecodeError::Truncated));
+        assert_eq!(decode(b"PNG").err(), Some(DecodeError::InvalidHeader));
+
+        // impossible sizes fail before the pixels are allocated
+        let mut huge = bmp.clone();
+        huge[22..26].copy_from_slice(&[0, 0, 0, 0x80]);
+        assert_eq!(decode(&huge).err(), Some(DecodeError::InvalidHeader));
+        huge[22..26].copy_from_slice(&[0, 0x70, 0, 0]);
+        assert_eq!(decode(&huge).err(), Some(DecodeError::Truncated));
+    }
+}
@@ -0,0 +1,113 @@
+//! Bitmaps and the decoders for the formats they are stored in.
+//!
+//! Decoded images are kept as colors in memory, raw framebuffer dumps in the
+//! pixel format of a display are read in place, e.g. from flash, so they do
+//! not take up any RAM.
+
+use collections::Vec;
+use core::fmt;
+
+use draw::Color;
+use draw::PixelFormat;
+#[cfg(feature = "board")]
+use draw::Surface;
+
+pub mod bmp;
+pub mod qoi;
+pub mod raw;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    // not the expected format at all
+    InvalidHeader,
+    // a variant of the format that is not supported, e.g. compressed BMP
+    Unsupported,
+    // the data ends before all pixels were read
+    Truncated,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match *self {
+            DecodeError::InvalidHeader => "invalid image header",
+            DecodeError::Unsupported => "unsupported image format",
+            DecodeError::Truncated => "image data is truncated",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+pub struct Bitmap {
+    width: i32,
+    height: i32,
+    pixels: Pixels,
+}
+
+enum Pixels {
+    Colors(Vec<Color>),
+    // rows of `width` pixels without padding
+    Raw {
+        data: &'static [u8],
+        format: PixelFormat,
+    },
+}
+
+impl Bitmap {
+    /// A bitmap of the colors row by row, there have to be `width` times
+    /// `height` of them.
+    pub fn new(width: i32, height: i32, pixels: Vec<Color>) -> Bitmap {
+        assert_eq!(pixels.len(), (width * height) as usize);
+
+        Bitmap {
+            width: width,
+            height: height,
+            pixels: Pixels::Colors(pixels),
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Where the pixels are in memory, for the DMA2D to read them.
+    #[cfg(feature = "board")]
+    pub fn surface(&self) -> Surface {
+        match self.pixels {
+            Pixels::Colors(ref colors) => {
+                Surface {
+                    addr: colors.as_ptr() as usize,
+                    stride: self.width * 4,
+                    format: PixelFormat::Argb8888,
+                    swap_red_blue: true,
+                }
+            }
+            Pixels::Raw { data, format } => {
+                Surface {
+                    addr: data.as_ptr() as usize,
+                    stride: self.width * format.bytes_per_pixel(),
+                    format: format,
+                    swap_red_blue: false,
+                }
+            }
+        }
+    }
+
+    pub fn get_pixel(&self, x: i32, y: i32) -> Color {
+        let index = (y * self.width + x) as usize;
+        match self.pixels {
+            Pixels::Colors(ref colors) => colors[index],
+            Pixels::Raw { data, format } => {
+                let bytes = format.bytes_per_pixel() as usize;
+                let value = data[index * bytes..(index + 1) * bytes]
+                    .iter()
+                    .rev()
+                    .fold(0, |value, &byte| value << 8 | byte as u32);
+                format.decode(value)
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! The Quite OK Image format, losslessly compressed and simple enough to be
+//! decoded on the board.
+
+use collections::Vec;
+
+use draw::Color;
+use image::Bitmap;
+use image::DecodeError;
+
+const HEADER_SIZE: usize = 14;
+
+const OP_RGB: u8 = 0xFE;
+const OP_RGBA: u8 = 0xFF;
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+const MASK: u8 = 0xC0;
+
+fn hash(color: &Color) -> usize {
+    (color.red as usize * 3 + color.green as usize * 5 + color.blue as usize * 7 +
+     color.alpha as usize * 11) % 64
+}
+
+pub fn decode(data: &[u8]) -> Result<Bitmap, DecodeError> {
+    if data.len() < HEADER_SIZE || &data[..4] != b"qoif" {
+        return Err(DecodeError::InvalidHeader);
+    }
+
+    let be32 = |offset: usize| {
+        data[offset..offset + 4].iter().fold(0u32, |value, &byte| value << 8 | byte as u32)
+    };
+    let width = be32(4);
+    let height = be32(8);
+    if width == 0 || height == 0 || width > 0x7FFF || height > 0x7FFF {
+        return Err(DecodeError::InvalidHeader);
+    }
+
+    // a run byte encodes at most 62 pixels, a larger image cannot be in the
+    // data and is not allocated
+    let count = (width * height) as usize;
+    if count > (data.len() - HEADER_SIZE) * 62 {
+        return Err(DecodeError::Truncated);
+    }
+    let mut pixels = Vec::with_capacity(count);
+    let mut index = [Color::rgba(0, 0, 0, 0); 64];
+    let mut color = Color::rgba(0, 0, 0, 255);
+    let mut position = HEADER_SIZE;
+    let mut next = || {
+        let byte = data.get(position).cloned();
+        position += 1;
+        byte.ok_or(DecodeError::Truncated)
+    };
+
+    while pixels.len() < count {
+        let op = next()?;
+        let mut run = 1;
+
+        if op == OP_RGB {
+            color.red = next()?;
+            color.green = next()?;
+            color.blue = next()?;
+        } else if op == OP_RGBA {
+            color.red = next()?;
+            color.green = next()?;
+            color.blue = next()?;
+            color.alpha = next()?;
+        } else {
+            match op & MASK {
+                OP_INDEX => color = index[(op & 0x3F) as usize],
+                OP_DIFF => {
+                    color.red = color.red.wrapping_add((op >> 4 & 0x03).wrapping_sub(2));
+                    color.green = color.green.wrapping_add((op >> 2 & 0x03).wrapping_sub(2));
+                    color.blue = color.blue.wrapping_add((op & 0x03).wrapping_sub(2));
+                }
+                OP_LUMA => {
+                    let second = next()?;
+                    let green = (op & 0x3F).wrapping_sub(32);
+                    color.red = color.red
+                        .wrapping_add(green.wrapping_add(second >> 4).wrapping_sub(8));
+                    color.green = color.green.wrapping_add(green);
+                    color.blue = color.blue
+                        .wrapping_add(green.wrapping_add(second & 0x0F).wrapping_sub(8));
+                }
+                // the run of the previous color, 0xC0 to 0xFD
+                _ => run = (op & 0x3F) as usize + 1,
+            }
+        }
+
+        index[hash(&color)] = color;
+        for _ in 0..run {
+            if pixels.len() < count {
+                pixels.push(color);
+            }
+        }
+    }
+
+    Ok(Bitmap::new(width as i32, height as i32, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operations() {
+        let red = Color::rgb(255, 0, 0);
+
+        // red, a diff to black, red from the index, a luma step and a run
+        let mut qoi = b"qoif".to_vec();
+        qoi.extend(&[0, 0, 0, 3, 0, 0, 0, 2, 4, 0]);
+        qoi.extend(&[0xFE, 255, 0, 0, 0x7A, 50, 0xAA, 0x88, 0xC1]);
+        let bitmap = decode(&qoi).unwrap();
+        assert_eq!(bitmap.get_pixel(0, 0), red);
+        assert_eq!(bitmap.get_pixel(1, 0), Color::rgb(0, 0, 0));
+        assert_eq!(bitmap.get_pixel(2, 0), red);
+        assert_eq!(bitmap.get_pixel(0, 1), Color::rgb(9, 10, 10));
+        assert_eq!(bitmap.get_pixel(2, 1), Color::rgb(9, 10, 10));
+        assert_eq!(decode(&qoi[..18]).err(), Some(DecodeError::Truncated));
+
+        let mut huge = qoi.clone();
+        huge[4..12].copy_from_slice(&[0, 0, 0x7F, 0xFF, 0, 0, 0x7F, 0xFF]);
+        assert_eq!(decode(&huge).err(), Some(DecodeError::Truncated));
+    }
+}
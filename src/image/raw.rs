@@ -0,0 +1,53 @@
+//! Framebuffer dumps, the pixels in a display format without any header.
+
+use draw::PixelFormat;
+use image::Bitmap;
+use image::DecodeError;
+use image::Pixels;
+
+/// Reads the pixels from `data` when the bitmap is drawn, multi-byte pixels
+/// are little endian like in the framebuffer.
+pub fn decode(data: &'static [u8],
+              width: i32,
+              height: i32,
+              format: PixelFormat)
+              -> Result<Bitmap, DecodeError> {
+    if width < 0 || height < 0 {
+        return Err(DecodeError::InvalidHeader);
+    }
+    let size = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|count| count.checked_mul(format.bytes_per_pixel() as usize));
+    match size {
+        Some(size) if size <= data.len() => {}
+        _ => return Err(DecodeError::Truncated),
+    }
+
+    Ok(Bitmap {
+        width: width,
+        height: height,
+        pixels: Pixels::Raw {
+            data: data,
+            format: format,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use draw::Color;
+
+    static RGB565: [u8; 4] = [0xFF, 0xFF, 0x00, 0xF8];
+
+    #[test]
+    fn pixels_in_place() {
+        let bitmap = decode(&RGB565, 2, 1, PixelFormat::Rgb565).unwrap();
+        assert_eq!(bitmap.get_pixel(0, 0), Color::rgb(255, 255, 255));
+        assert_eq!(bitmap.get_pixel(1, 0), Color::rgb(255, 0, 0));
+
+        assert!(decode(&RGB565, 2, 2, PixelFormat::Rgb565).is_err());
+        let max = ::core::i32::MAX;
+        assert!(decode(&RGB565, max, max, PixelFormat::Argb8888).is_err());
+    }
+}
@@ -0,0 +1,26 @@
+//! Sources of touch input.
+//!
+//! The main loop polls an `InputSource` once per iteration instead of the
+//! touch controller directly, so the same loop can be driven by the FT5336,
+//! the simulator or a recorded replay.
+
+use collections::Vec;
+
+pub mod replay;
+#[cfg(feature = "board")]
+mod touch_panel;
+
+#[cfg(feature = "board")]
+pub use self::touch_panel::TouchPanel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TouchPoint {
+    pub id: u8,
+    pub x: i32,
+    pub y: i32,
+}
+
+pub trait InputSource {
+    /// Returns all contacts that are currently on the panel.
+    fn poll(&mut self, ticks: usize) -> Vec<TouchPoint>;
+}
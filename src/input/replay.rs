@@ -0,0 +1,211 @@
+//! Recording and replaying of touch input.
+//!
+//! A recording is plain text with one sample per line: the tick count
+//! followed by the contacts as `id:x,y`, e.g.
+//!
+//! ```text
+//! # a short drag
+//! 0 0:120,40
+//! 10 0:125,40
+//! 20
+//! ```
+//!
+//! A sample stays active until the next one, so a line with only the tick
+//! count releases all fingers. Empty lines and lines starting with `#` are
+//! ignored.
+
+use collections::String;
+use collections::Vec;
+use core::fmt;
+use core::fmt::Write;
+
+use input::InputSource;
+use input::TouchPoint;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub ticks: usize,
+    pub touches: Vec<TouchPoint>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    // 1-based line number of the invalid sample
+    pub line: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid touch sample in line {}", self.line)
+    }
+}
+
+pub fn parse(text: &str) -> Result<Vec<Sample>, ParseError> {
+    let mut samples: Vec<Sample> = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let error = ParseError { line: i + 1 };
+        let mut fields = line.split_whitespace();
+
+        let ticks = match fields.next().map(|f| f.parse::<usize>()) {
+            Some(Ok(ticks)) => ticks,
+            _ => return Err(error),
+        };
+        if let Some(last) = samples.last() {
+            if ticks < last.ticks {
+                return Err(error);
+            }
+        }
+
+        let mut touches = Vec::new();
+        for field in fields {
+            match parse_touch(field) {
+                Some(touch) => touches.push(touch),
+                None => return Err(error),
+            }
+        }
+
+        samples.push(Sample {
+            ticks: ticks,
+            touches: touches,
+        });
+    }
+
+    Ok(samples)
+}
+
+fn parse_touch(field: &str) -> Option<TouchPoint> {
+    let mut id_and_pos = field.splitn(2, ':');
+    let id = id_and_pos.next().and_then(|id| id.parse::<u8>().ok());
+    let mut pos = match id_and_pos.next() {
+        Some(pos) => pos.splitn(2, ','),
+        None => return None,
+    };
+    let x = pos.next().and_then(|x| x.parse::<i32>().ok());
+    let y = pos.next().and_then(|y| y.parse::<i32>().ok());
+
+    match (id, x, y) {
+        (Some(id), Some(x), Some(y)) => {
+            Some(TouchPoint {
+                id: id,
+                x: x,
+                y: y,
+            })
+        }
+        _ => None,
+    }
+}
+
+pub fn format_sample(ticks: usize, touches: &[TouchPoint]) -> String {
+    let mut res = String::new();
+    write!(res, "{}", ticks).unwrap();
+    for touch in touches {
+        write!(res, " {}:{},{}", touch.id, touch.x, touch.y).unwrap();
+    }
+    res
+}
+
+/// Plays a recording back, the first poll corresponds to the first sample.
+pub struct Replay {
+    samples: Vec<Sample>,
+    start: Option<usize>,
+}
+
+impl Replay {
+    pub fn new(samples: Vec<Sample>) -> Replay {
+        Replay {
+            samples: samples,
+            start: None,
+        }
+    }
+
+    fn elapsed(&self, ticks: usize) -> usize {
+        let first = self.samples.first().map(|s| s.ticks).unwrap_or(0);
+        match self.start {
+            // ticks before the start, e.g. after the counter wrapped, are no time
+            Some(start) => first + ticks.saturating_sub(start),
+            None => first,
+        }
+    }
+
+    /// True once the last sample was reached.
+    pub fn is_finished(&self, ticks: usize) -> bool {
+        match self.samples.last() {
+            Some(last) => self.start.is_some() && self.elapsed(ticks) >= last.ticks,
+            None => true,
+        }
+    }
+}
+
+impl InputSource for Replay {
+    fn poll(&mut self, ticks: usize) -> Vec<TouchPoint> {
+        if self.start.is_none() {
+            self.start = Some(ticks);
+        }
+
+        let elapsed = self.elapsed(ticks);
+        match self.samples.iter().take_while(|s| s.ticks <= elapsed).last() {
+            Some(sample) => sample.touches.clone(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Forwards the input of another source and writes every change as a sample
+/// line, e.g. to the semi-hosting stdout on the board.
+pub struct Recorder<S: InputSource, W: Write> {
+    source: S,
+    output: W,
+    last: Option<Vec<TouchPoint>>,
+}
+
+impl<S: InputSource, W: Write> Recorder<S, W> {
+    pub fn new(source: S, output: W) -> Recorder<S, W> {
+        Recorder {
+            source: source,
+            output: output,
+            last: None,
+        }
+    }
+}
+
+impl<S: InputSource, W: Write> InputSource for Recorder<S, W> {
+    fn poll(&mut self, ticks: usize) -> Vec<TouchPoint> {
+        let touches = self.source.poll(ticks);
+
+        if self.last.as_ref() != Some(&touches) {
+            let _ = writeln!(self.output, "{}", format_sample(ticks, &touches));
+            self.last = Some(touches.clone());
+        }
+
+        touches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::TouchPoint;
+
+    #[test]
+    fn parse_and_format() {
+        let samples = parse("# comment\n0 0:1,2 1:30,40\n\n10\n").unwrap();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].touches[1],
+                   TouchPoint {
+                       id: 1,
+                       x: 30,
+                       y: 40,
+                   });
+        assert!(samples[1].touches.is_empty());
+        assert_eq!(format_sample(0, &samples[0].touches), "0 0:1,2 1:30,40");
+
+        assert_eq!(parse("0 0:1,2\n5 0:1").unwrap_err().line, 2);
+        assert_eq!(parse("10\n5").unwrap_err().line, 2);
+    }
+}
@@ -0,0 +1,58 @@
+use collections::Vec;
+use stm32f7::i2c;
+
+use input::InputSource;
+use input::TouchPoint;
+
+const FT5336_ADDRESS: i2c::Address = i2c::Address::bits_7(0b0111000);
+// the number of contacts is in the lower nibble
+const FT5336_STATUS_REGISTER: u8 = 0x02;
+// The first of the six registers of each contact, P_XH, P_XL, P_YH and P_YL
+// come first. The controller's x and y are the display's y and x.
+const FT5336_DATA_REGISTERS: [u8; 5] = [0x03, 0x09, 0x0F, 0x15, 0x1B];
+
+/// The FT5336 touch controller of the display.
+pub struct TouchPanel<'a> {
+    i2c: &'a mut i2c::I2C,
+}
+
+impl<'a> TouchPanel<'a> {
+    pub fn new(i2c: &'a mut i2c::I2C) -> TouchPanel<'a> {
+        TouchPanel { i2c: i2c }
+    }
+}
+
+impl<'a> InputSource for TouchPanel<'a> {
+    fn poll(&mut self, _ticks: usize) -> Vec<TouchPoint> {
+        let mut res = Vec::new();
+
+        // the contacts are read one by one, the id is the touch id the
+        // controller keeps for a finger until it is lifted, in the upper
+        // nibble of P_YH
+        let result = self.i2c.connect::<u8, _>(FT5336_ADDRESS, |mut conn| {
+            let count = conn.read(FT5336_STATUS_REGISTER)? & 0x0F;
+            if count as usize > FT5336_DATA_REGISTERS.len() {
+                return Ok(());
+            }
+
+            for &register in FT5336_DATA_REGISTERS.iter().take(count as usize) {
+                let x_high = conn.read(register)?;
+                let x_low = conn.read(register + 1)?;
+                let y_high = conn.read(register + 2)?;
+                let y_low = conn.read(register + 3)?;
+                res.push(TouchPoint {
+                    id: y_high >> 4,
+                    x: ((y_high as i32 & 0x0F) << 8) | y_low as i32,
+                    y: ((x_high as i32 & 0x0F) << 8) | x_low as i32,
+                });
+            }
+            Ok(())
+        });
+
+        // a failed transfer is taken as no contacts
+        if result.is_err() {
+            res.clear();
+        }
+        res
+    }
+}
@@ -1,14 +1,14 @@
 use forms::form::*;
 use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
 use util::*;
 use collections::boxed::Box;
 use collections::Vec;
 
 use util::layout_funcs::BorderArea;
-use util::sizes;
 
-use draw;
-use lcd::Color;
+use draw::display;
+use draw::DrawTarget;
 
 
 pub struct BorderLayout {
@@ -26,12 +26,7 @@ impl BorderLayout {
     pub fn new(bb: bounding_box::BoundingBox) -> BorderLayout {
         BorderLayout {
             bounding_box: bb,
-            outer_bounding_box: BoundingBox {
-                x: 0,
-                y: 0,
-                width: sizes::RES_X,
-                height: sizes::RES_Y,
-            },
+            outer_bounding_box: display::current().get_bounding_box(),
             top_element: None,
             bottom_element: None,
             left_element: None,
@@ -41,7 +36,7 @@ impl BorderLayout {
         }
     }
 
-    pub fn draw_area(&self) -> bool {
+    pub fn draw_area(&self, target: &mut DrawTarget) -> bool {
         let all_el = [&self.top_element,
                       &self.bottom_element,
                       &self.left_element,
@@ -52,10 +47,10 @@ impl BorderLayout {
             match i {
                 // why two references??? thought into_iter would not create another?? very strange??
                 // dereferencing is prohibited due to moving... this is also weird??
-                &&Some(ref el) => el.draw(),
+                &&Some(ref el) => draw_child(target, &self.bounding_box, &**el),
                 &&None => {}
             }
-            //i.draw();
+            //i.draw(target);
         }
         true
     }
@@ -171,21 +166,30 @@ impl Form for BorderLayout {
         self.movable = value;
     }
 
-    fn draw(&self) -> () {
-        self.draw_area();
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        self.draw_area(target);
     }
 
-    fn clear(&self) -> () {
-        let color = Color::rgba(0, 0, 0, 0);
-        draw::fill_rectangle(self.bounding_box.x,
-                             self.bounding_box.y,
-                             self.bounding_box.width,
-                             self.bounding_box.height,
-                             color);
+    fn redraw(&mut self, target: &mut DrawTarget, region: &BoundingBox) -> () {
+        let opts = vec![&mut self.top_element,
+                        &mut self.bottom_element,
+                        &mut self.left_element,
+                        &mut self.right_element,
+                        &mut self.center_element];
+
+        for i in opts {
+            if let &mut Some(ref mut form) = i {
+                redraw_child(target, &self.bounding_box, &mut **form, region);
+            }
+        }
+    }
+
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box.with_outline());
     }
 
-    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool) {
-        self.clear();
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box);
 
         let outer_if_top = if top {
             Some(&self.outer_bounding_box)
@@ -205,11 +209,11 @@ impl Form for BorderLayout {
 
             for i in opts {
                 if let &mut Some(ref mut form) = i {
-                    form.move_form(moved_x, moved_y, false);
+                    form.move_form(moved_x, moved_y, false, dirty);
                 }
             }
         }
 
-        self.draw();
+        dirty.add(&self.bounding_box);
     }
 }
@@ -3,9 +3,10 @@ use util::*;
 use collections::boxed::Box;
 use collections::Vec;
 use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
 
-use draw;
-use lcd::Color;
+use draw::display;
+use draw::DrawTarget;
 
 pub struct HorizontalLayout {
     pub bounding_box: bounding_box::BoundingBox,
@@ -19,21 +20,16 @@ impl HorizontalLayout {
     pub fn new(bounding_box: BoundingBox) -> HorizontalLayout {
         HorizontalLayout {
             bounding_box: bounding_box,
-            outer_bounding_box: BoundingBox {
-                x: 0,
-                y: 0,
-                width: sizes::RES_X,
-                height: sizes::RES_Y,
-            },
+            outer_bounding_box: display::current().get_bounding_box(),
             elements: Vec::new(),
             movable: false,
             proportions: Vec::new(),
         }
     }
 
-    pub fn draw_area(&self) -> bool {
+    pub fn draw_area(&self, target: &mut DrawTarget) -> bool {
         for i in &self.elements {
-            i.draw();
+            draw_child(target, &self.bounding_box, &**i);
         }
 
         true
@@ -145,21 +141,22 @@ impl Form for HorizontalLayout {
         self.movable = value;
     }
 
-    fn clear(&self) -> () {
-        let color = Color::rgba(0, 0, 0, 0);
-        draw::fill_rectangle(self.bounding_box.x,
-                             self.bounding_box.y,
-                             self.bounding_box.width,
-                             self.bounding_box.height,
-                             color);
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box.with_outline());
     }
 
-    fn draw(&self) -> () {
-        self.draw_area();
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        self.draw_area(target);
     }
 
-    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool) {
-        self.clear();
+    fn redraw(&mut self, target: &mut DrawTarget, region: &BoundingBox) -> () {
+        for i in &mut self.elements {
+            redraw_child(target, &self.bounding_box, &mut **i, region);
+        }
+    }
+
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box);
 
         let outer_if_top = if top {
             Some(&self.outer_bounding_box)
@@ -172,9 +169,9 @@ impl Form for HorizontalLayout {
 
         for i in &mut self.elements {
             i.set_outer_bounding_box(self.bounding_box.clone());
-            i.move_form(moved_x, moved_y, false);
+            i.move_form(moved_x, moved_y, false, dirty);
         }
 
-        self.draw();
+        dirty.add(&self.bounding_box);
     }
 }
@@ -2,8 +2,10 @@ mod border_layout;
 mod horizontal_layout;
 mod vertical_layout;
 mod move_box;
+mod scroll_view;
 
 pub use self::border_layout::*;
 pub use self::horizontal_layout::*;
 pub use self::vertical_layout::*;
 pub use self::move_box::*;
+pub use self::scroll_view::*;
@@ -1,21 +1,24 @@
 use forms::form::*;
 use util::*;
-use util::sizes;
 use collections::boxed::Box;
 use collections::Vec;
 use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
 
 use util::layout_funcs::BorderArea;
 
-use draw;
-use lcd::Color;
+use draw::display;
+use draw::DrawTarget;
+use forms::theme::Style;
+use forms::theme::Theme;
+use forms::theme::draw_styled_box;
 
 pub struct MoveBox {
     pub bounding_box: bounding_box::BoundingBox,
     outer_bounding_box: bounding_box::BoundingBox,
     pub elements: Vec<Box<Form>>,
     movable: bool,
-    border_color: Color,
+    style: Style,
 }
 
 impl MoveBox {
@@ -23,24 +26,15 @@ impl MoveBox {
         MoveBox {
             elements: Vec::<Box<Form>>::new(),
             bounding_box: bounding_box,
-            outer_bounding_box: bounding_box::BoundingBox {
-                x: 0,
-                y: 0,
-                width: sizes::RES_X,
-                height: sizes::RES_Y,
-            },
+            outer_bounding_box: display::current().get_bounding_box(),
             movable: movable,
-            border_color: Color::from_hex(0xFFFFFF),
+            style: Theme::dark().frame,
         }
     }
-    pub fn draw_area(&self) -> bool {
-        draw::draw_rectangle(self.bounding_box.x,
-                             self.bounding_box.y,
-                             self.bounding_box.width,
-                             self.bounding_box.height,
-                             self.border_color);
+    pub fn draw_area(&self, target: &mut DrawTarget) -> bool {
+        draw_styled_box(target, &self.bounding_box, &self.style, false);
         for i in &self.elements {
-            i.draw();
+            draw_child(target, &self.bounding_box, &**i);
         }
         true
     }
@@ -94,21 +88,27 @@ impl Form for MoveBox {
         self.movable = movable;
     }
 
-    fn clear(&self) -> () {
-        let color = Color::rgba(0, 0, 0, 0);
-        draw::fill_rectangle(self.bounding_box.x,
-                             self.bounding_box.y,
-                             self.bounding_box.width,
-                             self.bounding_box.height,
-                             color);
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box.with_outline());
     }
 
-    fn draw(&self) -> () {
-        self.draw_area();
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        self.draw_area(target);
     }
 
-    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool) {
-        self.clear();
+    fn redraw(&mut self, target: &mut DrawTarget, region: &BoundingBox) -> () {
+        if !self.bounding_box.intersects(region) {
+            return;
+        }
+
+        draw_styled_box(target, &self.bounding_box, &self.style, false);
+        for element in &mut self.elements {
+            redraw_child(target, &self.bounding_box, &mut **element, region);
+        }
+    }
+
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        dirty.add(&self.bounding_box);
 
         let outer_if_top = if top {
             Some(&self.outer_bounding_box)
@@ -121,9 +121,16 @@ impl Form for MoveBox {
 
         for element in &mut self.elements {
             element.set_outer_bounding_box(self.bounding_box.clone());
-            element.move_form(moved_x, moved_y, false);
+            element.move_form(moved_x, moved_y, false, dirty);
         }
 
-        self.draw();
+        dirty.add(&self.bounding_box);
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) -> () {
+        self.style = theme.frame;
+        for element in &mut self.elements {
+            element.apply_theme(theme);
+        }
     }
 }
@@ -0,0 +1,337 @@
+use collections::boxed::Box;
+use core::cmp::max;
+use core::cmp::min;
+use core::iter;
+
+use action::event::Event;
+use action::event::EventContext;
+use draw::display;
+use draw::DrawTarget;
+use forms::form::*;
+use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
+
+// Positions and velocities are fixed point with 8 fractional bits,
+// velocities are in 1/256 pixels per tick.
+const ONE: i32 = 256;
+// A released fling loses about a third of its velocity every 100 ticks.
+const FRICTION_TICKS: i32 = 300;
+// Below this velocity the content stops.
+const MIN_VELOCITY: i32 = 8;
+// An overscroll springs back by a fifth every 16 ticks.
+const BOUNCE_TICKS: i32 = 80;
+
+// Scroll state of one direction, the position is the distance the content is
+// scrolled, 0 to `max` in pixels when not overscrolled.
+struct Axis {
+    position: i32,
+    velocity: i32,
+    max: i32,
+    // pixels dragged since the last tick, to measure the velocity
+    dragged: i32,
+}
+
+impl Axis {
+    fn new() -> Axis {
+        Axis {
+            position: 0,
+            velocity: 0,
+            max: 0,
+            dragged: 0,
+        }
+    }
+
+    fn overshoot(&self) -> i32 {
+        if self.position < 0 {
+            self.position
+        } else if self.position > self.max * ONE {
+            self.position - self.max * ONE
+        } else {
+            0
+        }
+    }
+
+    fn pixels(&self) -> i32 {
+        self.position / ONE
+    }
+
+    // The content follows the finger, beyond the ends only by half.
+    fn drag(&mut self, delta: i32) {
+        if self.max == 0 {
+            return;
+        }
+
+        self.dragged += delta;
+        if self.overshoot() == 0 {
+            self.position -= delta * ONE;
+        } else {
+            self.position -= delta * ONE / 2;
+        }
+    }
+
+    fn measure(&mut self, elapsed: i32) {
+        let velocity = -self.dragged * ONE / elapsed;
+        self.velocity = (self.velocity + velocity) / 2;
+        self.dragged = 0;
+    }
+
+    // Continues a released fling and springs back from an overscroll,
+    // returns false once the content came to rest.
+    fn animate(&mut self, elapsed: i32) -> bool {
+        self.position += self.velocity * elapsed;
+        self.velocity -= self.velocity * min(elapsed, FRICTION_TICKS) / FRICTION_TICKS;
+
+        let overshoot = self.overshoot();
+        if overshoot != 0 {
+            // the end of the content stops a fling quickly
+            self.velocity /= 2;
+            let back = overshoot * min(elapsed, BOUNCE_TICKS) / BOUNCE_TICKS;
+            if overshoot.abs() < ONE || back == 0 {
+                self.position -= overshoot;
+            } else {
+                self.position -= back;
+            }
+        }
+
+        if self.velocity.abs() < MIN_VELOCITY {
+            self.velocity = 0;
+        }
+        self.velocity != 0 || self.overshoot() != 0
+    }
+}
+
+/// Shows a part of a larger child and scrolls it with the finger.
+///
+/// The child keeps its size when it is set, the scroll view only moves it
+/// within its own bounding box and clips its drawing to it. The content only
+/// scrolls once the finger moved a few pixels, so tapping it still clicks.
+/// After the finger is lifted the content keeps moving and slows down,
+/// scrolling past the ends springs back.
+pub struct ScrollView {
+    bounding_box: BoundingBox,
+    outer_bounding_box: BoundingBox,
+    child: Option<Box<Form>>,
+    movable: bool,
+    scroll_x: Axis,
+    scroll_y: Axis,
+    // scroll offset the child is currently moved by, in pixels
+    offset_x: i32,
+    offset_y: i32,
+    dragging: bool,
+    flinging: bool,
+    last_ticks: Option<usize>,
+}
+
+impl ScrollView {
+    pub fn new(bounding_box: BoundingBox) -> ScrollView {
+        ScrollView {
+            bounding_box: bounding_box,
+            outer_bounding_box: display::current().get_bounding_box(),
+            child: None,
+            // scrolling is done by dragging the scroll view itself
+            movable: true,
+            scroll_x: Axis::new(),
+            scroll_y: Axis::new(),
+            offset_x: 0,
+            offset_y: 0,
+            dragging: false,
+            flinging: false,
+            last_ticks: None,
+        }
+    }
+
+    /// Sets the content, its width and height are kept but at least the
+    /// size of the scroll view.
+    pub fn set_child(&mut self, child: Box<Form>) -> () {
+        self.child = Some(child);
+        self.scroll_x = Axis::new();
+        self.scroll_y = Axis::new();
+        self.layout_child();
+    }
+
+    // Returns how far the content is scrolled, in pixels.
+    pub fn get_scroll_offset(&self) -> (i32, i32) {
+        (self.offset_x, self.offset_y)
+    }
+
+    pub fn scroll_to(&mut self, x: i32, y: i32, dirty: &mut DirtyRegion) {
+        self.scroll_x.position = max(0, min(x, self.scroll_x.max)) * ONE;
+        self.scroll_y.position = max(0, min(y, self.scroll_y.max)) * ONE;
+        self.scroll_x.velocity = 0;
+        self.scroll_y.velocity = 0;
+        self.flinging = false;
+        self.apply_scroll(dirty);
+    }
+
+    fn layout_child(&mut self) {
+        let view = self.bounding_box.clone();
+        if let Some(ref mut child) = self.child {
+            let (width, height) = {
+                let content = child.get_bounding_box();
+                (max(content.width, view.width), max(content.height, view.height))
+            };
+            self.scroll_x.max = width - view.width;
+            self.scroll_y.max = height - view.height;
+            self.offset_x = self.scroll_x.pixels();
+            self.offset_y = self.scroll_y.pixels();
+
+            child.set_bounding_box(BoundingBox {
+                x: view.x - self.offset_x,
+                y: view.y - self.offset_y,
+                width: width,
+                height: height,
+            });
+            child.set_outer_bounding_box(view);
+        }
+    }
+
+    // Moves the child to the scroll position.
+    fn apply_scroll(&mut self, dirty: &mut DirtyRegion) {
+        let dx = self.scroll_x.pixels() - self.offset_x;
+        let dy = self.scroll_y.pixels() - self.offset_y;
+        if dx == 0 && dy == 0 {
+            return;
+        }
+
+        self.offset_x += dx;
+        self.offset_y += dy;
+        if let Some(ref mut child) = self.child {
+            // only the visible part has to be drawn again
+            let mut content_dirty = DirtyRegion::new();
+            child.move_form(-dx, -dy, false, &mut content_dirty);
+            for area in content_dirty.rects() {
+                if let Some(visible) = area.intersection(&self.bounding_box) {
+                    dirty.add(&visible);
+                }
+            }
+        }
+    }
+}
+
+impl Form for ScrollView {
+    fn get_bounding_box(&mut self) -> &mut BoundingBox {
+        &mut self.bounding_box
+    }
+
+    fn set_bounding_box(&mut self, bounding_box: BoundingBox) -> () {
+        self.bounding_box = bounding_box;
+        self.layout_child();
+    }
+
+    fn set_outer_bounding_box(&mut self, bounding_box: BoundingBox) {
+        self.outer_bounding_box = bounding_box;
+    }
+
+    fn get_children<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Form> + 'a> {
+        match self.child {
+            Some(ref mut child) => Box::new(iter::once::<&'a mut Form>(&mut **child)),
+            None => Box::new(iter::empty()),
+        }
+    }
+
+    fn is_clickable(&mut self) -> Option<&mut Clickable> {
+        Some(self)
+    }
+
+    fn is_movable(&mut self) -> bool {
+        self.movable
+    }
+
+    fn set_movable(&mut self, value: bool) -> () {
+        self.movable = value;
+    }
+
+    fn clear(&self, target: &mut DrawTarget) -> () {
+        target.clear_area(&self.bounding_box);
+    }
+
+    // The content is clipped to the scroll view itself, not including the
+    // outline row and column.
+    fn draw(&self, target: &mut DrawTarget) -> () {
+        if let Some(ref child) = self.child {
+            target.push_clip(&self.bounding_box);
+            child.draw(target);
+            target.pop_clip();
+        }
+    }
+
+    fn redraw(&mut self, target: &mut DrawTarget, region: &BoundingBox) -> () {
+        if !self.bounding_box.intersects(region) {
+            return;
+        }
+
+        if let Some(ref mut child) = self.child {
+            target.push_clip(&self.bounding_box);
+            child.redraw(target, region);
+            target.pop_clip();
+        }
+    }
+
+    // Dragging the scroll view scrolls the content, moving it together with
+    // its parent moves the viewport.
+    fn move_form(&mut self, dir_x: i32, dir_y: i32, top: bool, dirty: &mut DirtyRegion) {
+        if top {
+            self.scroll_x.drag(dir_x);
+            self.scroll_y.drag(dir_y);
+            self.apply_scroll(dirty);
+            return;
+        }
+
+        dirty.add(&self.bounding_box);
+        let (moved_x, moved_y) = self.bounding_box.move_in_direction(dir_x, dir_y, None);
+        if let Some(ref mut child) = self.child {
+            child.set_outer_bounding_box(self.bounding_box.clone());
+            child.move_form(moved_x, moved_y, false, dirty);
+        }
+        dirty.add(&self.bounding_box);
+    }
+
+    fn tick(&mut self, ticks: usize, dirty: &mut DirtyRegion) -> () {
+        let elapsed = match self.last_ticks {
+            Some(last_ticks) => ticks.saturating_sub(last_ticks) as i32,
+            None => 0,
+        };
+        self.last_ticks = Some(ticks);
+
+        if elapsed > 0 {
+            if self.dragging {
+                self.scroll_x.measure(elapsed);
+                self.scroll_y.measure(elapsed);
+            } else if self.flinging {
+                let moving_x = self.scroll_x.animate(elapsed);
+                let moving_y = self.scroll_y.animate(elapsed);
+                self.flinging = moving_x || moving_y;
+                self.apply_scroll(dirty);
+            }
+        }
+
+        if let Some(ref mut child) = self.child {
+            child.tick(ticks, dirty);
+        }
+    }
+}
+
+impl Clickable for ScrollView {
+    fn handle_event(&mut self, event: &Event, _ctx: &mut EventContext) -> () {
+        match *event {
+            // a finger stops the content
+            Event::Pressed { .. } => {
+                self.flinging = false;
+                self.scroll_x.velocity = 0;
+                self.scroll_y.velocity = 0;
+            }
+            Event::DragStart { .. } => {
+                self.dragging = true;
+                self.scroll_x.dragged = 0;
+                self.scroll_y.dragged = 0;
+            }
+            // the content keeps moving or springs back
+            Event::Released { .. } |
+            Event::DragEnd { .. } => {
+                self.dragging = false;
+                self.flinging = true;
+            }
+            _ => {}
+        }
+    }
+}
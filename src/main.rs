@@ -1,23 +1,33 @@
-#![no_std]
-#![no_main]
+#![cfg_attr(feature = "board", no_std)]
+#![cfg_attr(feature = "board", no_main)]
 #![feature(collections)]
-#![feature(asm)]
-#![feature(compiler_builtins_lib)]
+#![feature(alloc)]
+#![cfg_attr(feature = "board", feature(asm))]
+#![cfg_attr(feature = "board", feature(compiler_builtins_lib))]
 
 #[macro_use]
 extern crate collections;
+extern crate alloc;
+#[cfg(feature = "board")]
 #[macro_use]
 extern crate stm32f7_discovery as stm32f7;
+#[cfg(not(feature = "board"))]
+extern crate core;
 
 extern crate arrayvec;
+#[cfg(feature = "board")]
 extern crate compiler_builtins;
 
 
+#[cfg(feature = "board")]
 mod semi_hosting;
 
 // Initialization routines for .data and .bss.
+#[cfg(feature = "board")]
 extern crate r0;
+#[cfg(feature = "board")]
 use stm32f7::{system_clock, board, embedded, sdram, lcd, i2c, touch};
+#[cfg(feature = "board")]
 use embedded::interfaces::gpio::{self, Gpio};
 
 mod forms;
@@ -28,8 +38,14 @@ mod layout;
 mod move_things;
 mod demo;
 mod application;
+mod input;
+mod font;
+mod image;
+#[cfg(feature = "simulator")]
+mod simulator;
 
 use util::bounding_box::BoundingBox;
+use collections::String;
 use collections::Vec;
 use collections::VecDeque;
 use forms::button::Button;
@@ -37,19 +53,31 @@ use forms::label::Label;
 
 use collections::boxed::Box;
 use forms::form::Form;
-use util::sizes;
 
+use util::format;
 use util::layout_funcs::BorderArea;
 
 use move_things::swipe;
 
+#[cfg(feature = "board")]
 use stm32f7::touch::Touch;
 //use stm32f7::arrayvec::ArrayVec;
 use arrayvec::ArrayVec;
 
 use application::view::View;
 use application::app::App;
+use action::event::EventContext;
+use draw::DrawTarget;
+#[cfg(feature = "board")]
+use draw::Framebuffer;
+use input::InputSource;
+
+#[cfg(feature = "simulator")]
+fn main() {
+    simulator::run();
+}
 
+#[cfg(feature = "board")]
 #[inline(never)]
 fn main(hw: board::Hardware) -> ! {
     let board::Hardware {
@@ -122,6 +150,11 @@ fn main(hw: board::Hardware) -> ! {
     layer_2.clear();
 
     stm32f7::init_stdout(lcd.layer_2().unwrap());
+    // the layers as the lcd module sets them up, the views take their size
+    // from it
+    let display = draw::display::DISCOVERY_F746;
+    draw::display::set_current(display);
+    let mut framebuffer = Framebuffer::new(display);
 
 
     let mut button = forms::button::Button::new(util::bounding_box::BoundingBox {
@@ -221,12 +254,16 @@ fn main(hw: board::Hardware) -> ! {
         height: 15,
     };
     let christian_text = Label::new(christian, "Christian");
-    back_button.set_child(Box::new(back_text));
-    button.set_child(Box::new(matthias_text));
-    button2.set_child(Box::new(joel_text));
-    button3.set_child(Box::new(christian_text));
+    back_button.set_label(back_text);
+    button.set_label(matthias_text);
+    button2.set_label(joel_text);
+    button3.set_label(christian_text);
 
-    back_button.set_action_on_click(clicked);
+    let mut clicks = 0;
+    back_button.set_action_on_click(move |button, ctx| {
+        clicks += 1;
+        clicked(button, clicks, ctx);
+    });
 
     let back_button_box = Box::new(back_button);
 
@@ -249,12 +286,13 @@ fn main(hw: board::Hardware) -> ! {
     move_hor_layout.set_proportions(prop);
     move_hor_layout.set_movable(false);
     let mut move_view = View::new(Box::new(move_hor_layout));
-    //move_view.draw();
-    
-    let mut v = demo::view_skins();
-    v.draw();
 
-    let mut touch_history = move_things::swipe::TouchHistory::new();
+    let mut app = App::with_back_buffer(demo::view_skins(), Box::new(Framebuffer::offscreen(display)));
+    //let mut app = App::new(move_view);
+
+    let mut touch_panel = input::TouchPanel::new(&mut i2c_3);
+    // To record a replay for the simulator, poll through a recorder instead:
+    //let mut touch_panel = input::replay::Recorder::new(touch_panel, semi_hosting::Stdout);
 
     let mut last_led_toggle = system_clock::ticks();
     loop {
@@ -266,26 +304,9 @@ fn main(hw: board::Hardware) -> ! {
             last_led_toggle = ticks;
         }
 
-        for touch in &touch::touches(&mut i2c_3).unwrap() {
-            //action::walker::walk(&mut move_view, touch.x as i32, touch.y as i32);
-            action::walker::walk(&mut v, touch.x as i32, touch.y as i32);
-
-            ////action::walker::walk(&mut move_hor_layout, touch.x as i32, touch.y as i32);
-        }
-
-        //: &Result<ArrayVec<[Touch; 5]>, i2c::Error>
-        let touches_result = touch::touches(&mut i2c_3).unwrap();
-
-        let mut input: Vec<(i32, i32)> = Vec::new();
-        for i in touches_result {
-            input.push((i.x as i32, i.y as i32));
-        }
-
-
-        touch_history.update(ticks, input);
-        //touch_history.check_for_object_moves(&mut move_view);
-        ////touch_history.check_for_object_moves(&mut move_hor_layout);
-
+        let touches = touch_panel.poll(ticks);
+        app.update(ticks, &touches);
+        app.render(&mut framebuffer);
 
         //let v: VecDeque<u32> = VecDeque::new();
         // check if there was an error or get ArrayVec otherwise for an update
@@ -300,26 +321,19 @@ fn main(hw: board::Hardware) -> ! {
 }
 
 
-fn clicked(form: &mut Button) {
-        let label = Label::new(BoundingBox{
-            x: 0,
-            y: 0,
-            width: 10,
-            height: 10,
-        }, "clicked!");
+fn clicked(form: &mut Button, clicks: i32, ctx: &mut EventContext) {
+    let mut text = String::from("clicked ");
+    text.push_str(&format::integer(clicks));
+    form.set_text(&text, ctx.get_dirty_region());
 
-        form.set_child(Box::new(label));
-    
     let width = form.get_border_width();
     match width {
         2 => form.set_border_width(10),
         _ => form.set_border_width(10),
     }
-
-    form.clear();
-    form.draw();
 }
 
+#[cfg(feature = "board")]
 #[no_mangle]
 pub unsafe extern "C" fn reset() -> ! {
     extern "C" {
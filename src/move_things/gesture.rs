@@ -0,0 +1,376 @@
+//! Recognizes gestures in the touches of the panel.
+//!
+//! The FT5336 reports up to five fingers with an id each. Single fingers
+//! produce taps, double taps, long presses and swipes, the first two fingers
+//! on the panel together produce pinch and rotate gestures. All math is done
+//! with integers.
+
+use collections::Vec;
+
+use input::TouchPoint;
+use util::math::atan2_deg;
+use util::math::isqrt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Positions are in screen coordinates, velocities in pixels per second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    Tap { x: i32, y: i32 },
+    DoubleTap { x: i32, y: i32 },
+    LongPress { x: i32, y: i32 },
+    // (x, y) is the position the finger went down
+    Swipe {
+        x: i32,
+        y: i32,
+        direction: Direction,
+        velocity_x: i32,
+        velocity_y: i32,
+    },
+    // scale of the distance between the fingers since they went down, in
+    // percent, (x, y) is the point between the fingers
+    Pinch { x: i32, y: i32, scale: i32 },
+    // clockwise rotation of the fingers since they went down, in degrees
+    Rotate { x: i32, y: i32, angle: i32 },
+}
+
+impl Gesture {
+    pub fn position(&self) -> (i32, i32) {
+        match *self {
+            Gesture::Tap { x, y } |
+            Gesture::DoubleTap { x, y } |
+            Gesture::LongPress { x, y } |
+            Gesture::Swipe { x, y, .. } |
+            Gesture::Pinch { x, y, .. } |
+            Gesture::Rotate { x, y, .. } => (x, y),
+        }
+    }
+}
+
+/// Thresholds of the recognizer, times are in ticks (ms) and distances in
+/// pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GestureConfig {
+    // longest touch that is still a tap
+    pub tap_max_ticks: usize,
+    // a finger moving further is not a tap or long press anymore
+    pub tap_max_distance: i32,
+    // longest time between the taps of a double tap
+    pub double_tap_ticks: usize,
+    pub double_tap_max_distance: i32,
+    pub long_press_ticks: usize,
+    pub swipe_min_distance: i32,
+    pub swipe_max_ticks: usize,
+    // change of the distance between two fingers before pinches are reported
+    pub pinch_min_distance: i32,
+    pub rotate_min_angle: i32,
+}
+
+impl GestureConfig {
+    pub fn new() -> GestureConfig {
+        GestureConfig {
+            tap_max_ticks: 300,
+            tap_max_distance: 10,
+            double_tap_ticks: 400,
+            double_tap_max_distance: 30,
+            long_press_ticks: 800,
+            swipe_min_distance: 60,
+            swipe_max_ticks: 500,
+            pinch_min_distance: 20,
+            rotate_min_angle: 10,
+        }
+    }
+}
+
+// A finger that is on the panel.
+struct Track {
+    id: u8,
+    start_x: i32,
+    start_y: i32,
+    start_ticks: usize,
+    x: i32,
+    y: i32,
+    // moved further than a tap may
+    moved: bool,
+    // held long enough for a long press, or part of a two finger gesture
+    consumed: bool,
+}
+
+// The two fingers of a pinch or rotation.
+struct Pair {
+    first: u8,
+    second: u8,
+    start_distance: i32,
+    start_angle: i32,
+    // last reported values, each change is reported once
+    scale: i32,
+    angle: i32,
+    pinching: bool,
+    rotating: bool,
+}
+
+pub struct GestureRecognizer {
+    config: GestureConfig,
+    tracks: Vec<Track>,
+    pair: Option<Pair>,
+    // position and time of the last tap, for double taps
+    last_tap: Option<(i32, i32, usize)>,
+}
+
+impl GestureRecognizer {
+    pub fn new() -> GestureRecognizer {
+        GestureRecognizer::with_config(GestureConfig::new())
+    }
+
+    pub fn with_config(config: GestureConfig) -> GestureRecognizer {
+        GestureRecognizer {
+            config: config,
+            tracks: Vec::new(),
+            pair: None,
+            last_tap: None,
+        }
+    }
+
+    pub fn get_config(&mut self) -> &mut GestureConfig {
+        &mut self.config
+    }
+
+    /// Feeds the touches of one poll and returns the recognized gestures.
+    pub fn update(&mut self, ticks: usize, touches: &[TouchPoint]) -> Vec<Gesture> {
+        let mut gestures = Vec::new();
+
+        // lifted fingers end their gesture
+        let mut i = 0;
+        while i < self.tracks.len() {
+            if touches.iter().any(|t| t.id == self.tracks[i].id) {
+                i += 1;
+                continue;
+            }
+            let track = self.tracks.remove(i);
+            self.lift(&track, ticks, &mut gestures);
+        }
+
+        for touch in touches {
+            if let Some(track) = self.tracks.iter_mut().find(|t| t.id == touch.id) {
+                track.x = touch.x;
+                track.y = touch.y;
+                if distance(track.start_x, track.start_y, track.x, track.y) >
+                   self.config.tap_max_distance {
+                    track.moved = true;
+                }
+                continue;
+            }
+            self.tracks.push(Track {
+                id: touch.id,
+                start_x: touch.x,
+                start_y: touch.y,
+                start_ticks: ticks,
+                x: touch.x,
+                y: touch.y,
+                moved: false,
+                consumed: false,
+            });
+        }
+
+        if self.tracks.len() == 1 {
+            let track = &mut self.tracks[0];
+            if !track.moved && !track.consumed &&
+               ticks.saturating_sub(track.start_ticks) >= self.config.long_press_ticks {
+                track.consumed = true;
+                gestures.push(Gesture::LongPress {
+                    x: track.start_x,
+                    y: track.start_y,
+                });
+            }
+        }
+
+        self.update_pair(&mut gestures);
+        gestures
+    }
+
+    fn lift(&mut self, track: &Track, ticks: usize, gestures: &mut Vec<Gesture>) {
+        if track.consumed {
+            return;
+        }
+
+        let duration = ticks.saturating_sub(track.start_ticks);
+        let dx = track.x - track.start_x;
+        let dy = track.y - track.start_y;
+
+        if !track.moved && duration <= self.config.tap_max_ticks {
+            let (x, y) = (track.start_x, track.start_y);
+            let double = match self.last_tap {
+                Some((last_x, last_y, last_ticks)) => {
+                    ticks.saturating_sub(last_ticks) <= self.config.double_tap_ticks &&
+                    distance(last_x, last_y, x, y) <= self.config.double_tap_max_distance
+                }
+                None => false,
+            };
+            if double {
+                self.last_tap = None;
+                gestures.push(Gesture::DoubleTap { x: x, y: y });
+            } else {
+                self.last_tap = Some((x, y, ticks));
+                gestures.push(Gesture::Tap { x: x, y: y });
+            }
+        } else if distance(0, 0, dx, dy) >= self.config.swipe_min_distance &&
+                  duration <= self.config.swipe_max_ticks {
+            let direction = if dx.abs() >= dy.abs() {
+                if dx > 0 { Direction::Right } else { Direction::Left }
+            } else if dy > 0 {
+                Direction::Down
+            } else {
+                Direction::Up
+            };
+            // a swipe can end within the same poll it started in
+            let duration = if duration == 0 { 1 } else { duration as i32 };
+            gestures.push(Gesture::Swipe {
+                x: track.start_x,
+                y: track.start_y,
+                direction: direction,
+                velocity_x: dx * 1000 / duration,
+                velocity_y: dy * 1000 / duration,
+            });
+        }
+    }
+
+    fn update_pair(&mut self, gestures: &mut Vec<Gesture>) {
+        let ended = match self.pair {
+            Some(ref pair) => {
+                self.track(pair.first).is_none() || self.track(pair.second).is_none()
+            }
+            None => false,
+        };
+        if ended {
+            self.pair = None;
+        }
+
+        if self.pair.is_none() && self.tracks.len() >= 2 {
+            let (first, second) = (&self.tracks[0], &self.tracks[1]);
+            self.pair = Some(Pair {
+                first: first.id,
+                second: second.id,
+                start_distance: distance(first.x, first.y, second.x, second.y),
+                start_angle: atan2_deg(second.y - first.y, second.x - first.x),
+                scale: 100,
+                angle: 0,
+                pinching: false,
+                rotating: false,
+            });
+        }
+
+        let (first, second) = match self.pair {
+            Some(ref pair) => (pair.first, pair.second),
+            None => return,
+        };
+        for track in &mut self.tracks {
+            if track.id == first || track.id == second {
+                track.consumed = true;
+            }
+        }
+
+        let (x1, y1) = self.track(first).map(|t| (t.x, t.y)).unwrap();
+        let (x2, y2) = self.track(second).map(|t| (t.x, t.y)).unwrap();
+        let (x, y) = ((x1 + x2) / 2, (y1 + y2) / 2);
+        let config = self.config;
+        let pair = self.pair.as_mut().unwrap();
+
+        let current = distance(x1, y1, x2, y2);
+        if (current - pair.start_distance).abs() >= config.pinch_min_distance {
+            pair.pinching = true;
+        }
+        if pair.pinching && pair.start_distance > 0 {
+            let scale = current * 100 / pair.start_distance;
+            if scale != pair.scale {
+                pair.scale = scale;
+                gestures.push(Gesture::Pinch {
+                    x: x,
+                    y: y,
+                    scale: scale,
+                });
+            }
+        }
+
+        let mut angle = atan2_deg(y2 - y1, x2 - x1) - pair.start_angle;
+        if angle > 180 {
+            angle -= 360;
+        } else if angle < -180 {
+            angle += 360;
+        }
+        if angle.abs() >= config.rotate_min_angle {
+            pair.rotating = true;
+        }
+        if pair.rotating && angle != pair.angle {
+            pair.angle = angle;
+            gestures.push(Gesture::Rotate {
+                x: x,
+                y: y,
+                angle: angle,
+            });
+        }
+    }
+
+    fn track(&self, id: u8) -> Option<&Track> {
+        self.tracks.iter().find(|t| t.id == id)
+    }
+}
+
+fn distance(x1: i32, y1: i32, x2: i32, y2: i32) -> i32 {
+    let (dx, dy) = (x1 - x2, y1 - y2);
+    isqrt((dx * dx + dy * dy) as u32) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(id: u8, x: i32, y: i32) -> TouchPoint {
+        TouchPoint { id: id, x: x, y: y }
+    }
+
+    #[test]
+    fn gestures() {
+        let mut recognizer = GestureRecognizer::new();
+
+        recognizer.update(0, &[touch(0, 50, 50)]);
+        assert_eq!(recognizer.update(100, &[]), vec![Gesture::Tap { x: 50, y: 50 }]);
+        recognizer.update(200, &[touch(0, 52, 51)]);
+        assert_eq!(recognizer.update(300, &[]),
+                   vec![Gesture::DoubleTap { x: 52, y: 51 }]);
+
+        recognizer.update(1000, &[touch(0, 100, 100)]);
+        assert_eq!(recognizer.update(1900, &[touch(0, 102, 100)]),
+                   vec![Gesture::LongPress { x: 100, y: 100 }]);
+        assert_eq!(recognizer.update(2000, &[]), vec![]);
+
+        recognizer.update(3000, &[touch(0, 100, 100)]);
+        recognizer.update(3100, &[touch(0, 200, 110)]);
+        assert_eq!(recognizer.update(3200, &[]),
+                   vec![Gesture::Swipe {
+                            x: 100,
+                            y: 100,
+                            direction: Direction::Right,
+                            velocity_x: 500,
+                            velocity_y: 50,
+                        }]);
+
+        recognizer.update(4000, &[touch(0, 100, 100), touch(1, 200, 100)]);
+        assert_eq!(recognizer.update(4100, &[touch(0, 50, 100), touch(1, 250, 100)]),
+                   vec![Gesture::Pinch { x: 150, y: 100, scale: 200 }]);
+        assert_eq!(recognizer.update(4200, &[touch(0, 150, 0), touch(1, 150, 200)]),
+                   vec![Gesture::Rotate { x: 150, y: 100, angle: 90 }]);
+        // lifting the fingers of a pinch is no tap
+        assert_eq!(recognizer.update(4300, &[]), vec![]);
+
+        // ticks that go backwards, e.g. after the counter wrapped, are no time
+        recognizer.update(5000, &[touch(0, 10, 10)]);
+        assert_eq!(recognizer.update(100, &[touch(0, 10, 10)]), vec![]);
+        assert_eq!(recognizer.update(50, &[]), vec![Gesture::Tap { x: 10, y: 10 }]);
+    }
+}
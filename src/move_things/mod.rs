@@ -1,3 +1,4 @@
+pub mod gesture;
 pub mod swipe;
 
 use util::bounding_box::BoundingBox;
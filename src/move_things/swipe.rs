@@ -1,189 +1,174 @@
-use core::num;
-use core::iter;
-use collections::VecDeque;
-use forms::form::Form;
 use collections::Vec;
-use util::bounding_box::BoundingBox;
-use util::sizes;
-use util::math::isqrt;
-use collections::boxed::Box;
-use draw;
-use stm32f7::lcd::Color;
 
-//use arrayvec::ArrayVec;
+use action::event::Event;
+use action::event::EventContext;
+use action::walker;
+use forms::form::Form;
+use input::TouchPoint;
+
+// A finger has to move further than this from where it went down before its
+// form is dragged, so the jitter of a tap does not move it.
+const DRAG_SLOP: i32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    // went down since the last check
+    Pressed,
+    Down,
+    // lifted since the last check
+    Released,
+}
 
-//use font_rs::float_impls::FloatImpls;
+// A finger on the panel, tracked by the id of the touch controller.
+struct Contact {
+    id: u8,
+    phase: Phase,
+    // path of the movable form under the finger, None if there is none
+    path: Option<Vec<usize>>,
+    dragging: bool,
+    x: i32,
+    y: i32,
+    // position the form was last moved to, where the finger went down
+    // until the drag started
+    last_x: i32,
+    last_y: i32,
+}
 
+/// Moves the movable forms with the fingers that went down on them.
+///
+/// Every contact keeps its form from press to release, so fingers coming
+/// close to each other or moving fast do not swap forms.
 pub struct TouchHistory {
-    // x_pos, y_pos, #ticks (round, in which touch occurred)
-    cur_touches: VecDeque<(i32, i32, usize)>,
+    contacts: Vec<Contact>,
 }
-// struct Movement {
-//     source_x : i32,
-//     source_y : i32,
-//     dir_x : i32,
-//     dir_y : i32
-// }
-// struct ObjectMovement {
-//     //object : &'a Form,
-//     source_x : i32,
-//     source_y : i32,
-//     dir_x : i32,
-//     dir_y : i32
-// }
+
 impl TouchHistory {
-    // add code here
     pub fn new() -> TouchHistory {
-        TouchHistory { cur_touches: VecDeque::new() }
+        TouchHistory { contacts: Vec::new() }
     }
 
-    //pub fn update(&mut self, cur_ticks: usize, new_touches: ArrayVec<(i32, i32)>) {
-    pub fn update(&mut self, cur_ticks: usize, new_touches: Vec<(i32, i32)>) {
-        let mut old = true;
-        // pop old touches
-        while old && !self.cur_touches.is_empty() {
-            //let mut cur_el = self.cur_touches.get(0).unwrap();
-            if cur_ticks - self.cur_touches.get(0).unwrap().2 > 500 {
-                // 1000ms could be made adaptable later:)
-                self.cur_touches.pop_front();
-            } else {
-                old = false;
-            }
-        }
-        // push new touches
-        for i in &new_touches {
-            self.cur_touches.push_back((i.0, i.1, cur_ticks));
-            //print..
-            //draw::draw_pixel(i.0, i.1, Color::from_hex(0xFFFFFF));
+    // Forgets the forms of the current contacts, e.g. after the view changed.
+    pub fn reset(&mut self) {
+        for contact in &mut self.contacts {
+            contact.path = None;
         }
+    }
 
-        /*print!("{}-", &self.cur_touches.len());
-        for i in &self.cur_touches {
-            print!("{}-", i.0);
+    /// Keeps the contact with the given id from dragging any form, e.g.
+    /// because the form it pressed follows it by itself. Has to be called
+    /// before the contact starts dragging.
+    pub fn capture(&mut self, id: u8) {
+        for contact in self.contacts.iter_mut().filter(|c| c.id == id) {
+            contact.path = None;
+            if contact.phase == Phase::Pressed {
+                contact.phase = Phase::Down;
+            }
         }
-        print!("---");*/
     }
 
-    pub fn check_for_object_moves(&mut self, root: &mut Form) {
-        //let mut moves = Vec::new();
-        let mut movements: Vec<Vec<(i32, i32, usize)>> = Vec::new();
-        for i in &self.cur_touches {
-            let mut found_match = false;
-            // currently takes the first that is good enough...
-            // 0..movements.len()
-            for j in 0..movements.len() {
-                let value = &mut movements[j];
-                let length = value.len();
-                // call update and clear old touches first, but you need to know the last one...
-                if get_square_distance(value[length - 1].0, value[length - 1].1, i.0, i.1) < 40 {
-                    value.push(*i);
-                    found_match = true;
+    /// Records the touches of one poll.
+    pub fn update(&mut self, touches: &[TouchPoint]) {
+        for contact in &mut self.contacts {
+            match touches.iter().find(|t| t.id == contact.id) {
+                Some(touch) => {
+                    contact.x = touch.x;
+                    contact.y = touch.y;
                 }
-            }
-            if !found_match {
-                movements.push(vec![*i]);
+                None => contact.phase = Phase::Released,
             }
         }
-        // Testteil
-        self.cur_touches = VecDeque::new();
-
-        for i in &movements {
-            let last = i.len() - 1;
-            self.cur_touches.push_back(i[last]);
-            /*for j in i {
-                print!("{},{}-", j.0, j.1);
+
+        for touch in touches {
+            if self.contacts.iter().any(|c| c.id == touch.id) {
+                continue;
             }
-            print!("---");*/
+            self.contacts.push(Contact {
+                id: touch.id,
+                phase: Phase::Pressed,
+                path: None,
+                dragging: false,
+                x: touch.x,
+                y: touch.y,
+                last_x: touch.x,
+                last_y: touch.y,
+            });
         }
-        // \Testteil
-
-        //let mut results: Vec<(&Form, i32, i32)> = Vec::new();
-        for i in movements {
-            //let res_check = check_for_hit(Box::new(iter::once(root)), i[0].0, i[0].1);
-            let res_check = check_for_hit(root.get_children(), i[0].0, i[0].1);
-            match res_check {
-                Some(form) => {
-                    // draw recursively from current note.
-                    /*
-                     * 1) clear() at parent
-                     * 2) draw() parent, // move!
-                     */
-
-
-
+    }
 
+    /// Moves the forms by the distance their fingers moved since the last
+    /// check. A form starts moving once its finger got further than a few
+    /// pixels away from where it went down, it then gets a drag start event,
+    /// drag move events while moving and a drag end event when the finger is
+    /// lifted.
+    pub fn check_for_object_moves(&mut self, root: &mut Form, ctx: &mut EventContext) {
+        for contact in &mut self.contacts {
+            if contact.phase == Phase::Pressed {
+                let mut path = Vec::new();
+                if find_movable(root, contact.x, contact.y, &mut path) {
+                    contact.path = Some(path);
+                }
+                contact.phase = Phase::Down;
+            }
 
-                    let delta_x = i[i.len() - 1].0 - i[0].0;
-                    let delta_y = i[i.len() - 1].1 - i[0].1;
+            let path = match contact.path {
+                Some(ref path) => path,
+                None => continue,
+            };
+
+            let dx = contact.x - contact.last_x;
+            let dy = contact.y - contact.last_y;
+            if !contact.dragging && dx * dx + dy * dy > DRAG_SLOP * DRAG_SLOP {
+                contact.dragging = true;
+                let event = Event::DragStart {
+                    x: contact.last_x,
+                    y: contact.last_y,
+                };
+                walker::bubble(root, path, &event, ctx);
+            }
 
-                    //form.move_form(i[i.len() - 1].0, i[i.len() - 1].1);
-                    if delta_x != 0 || delta_y != 0 {
-                        form.move_form(delta_x, delta_y, true);
-                    }
+            if contact.dragging && (dx != 0 || dy != 0) {
+                if let Some(form) = walker::form_at_path(root, path) {
+                    form.move_form(dx, dy, true, ctx.get_dirty_region());
                 }
-                None => {}
+                let event = Event::DragMove {
+                    x: contact.x,
+                    y: contact.y,
+                    dx: dx,
+                    dy: dy,
+                };
+                walker::bubble(root, path, &event, ctx);
+                contact.last_x = contact.x;
+                contact.last_y = contact.y;
             }
-        }
-    }
-}
 
-fn check_for_hit<'a>(objects: Box<Iterator<Item = &'a mut Form> + 'a>,
-                     x: i32,
-                     y: i32)
-                     -> Option<&'a mut Form> {
-    // recursively search for the movable object matched most precisely, move subtree from this
-    // object.
-    let mut last_mov_form: Option<&'a mut Form> = None;
-    //let mut found_el = false;
-    for i in objects {
-        let in_bb = i.get_bounding_box().is_in_bound(x, y);
-        let movable = i.is_movable();
-        if in_bb {
-            //let ret: &'a mut Form = i;
-
-            //iter::once::<&'a mut Form>(&mut **child)
-            //fn get_children<'a>(&'a mut self) -> Box<Iterator<Item = &'a mut Form> + 'a>;
-            if check_for_hit(i.get_children(), x, y).is_none() {
-                if movable {
-                    last_mov_form = Some(i);
-                    break;
-                }
-            } else {
-                last_mov_form = check_for_hit(i.get_children(), x, y);
+            if contact.phase == Phase::Released && contact.dragging {
+                let event = Event::DragEnd {
+                    x: contact.x,
+                    y: contact.y,
+                };
+                walker::bubble(root, path, &event, ctx);
             }
-            //break;
         }
+
+        self.contacts.retain(|c| c.phase != Phase::Released);
     }
-    last_mov_form
 }
 
-/*
-fn draw_recursively(note: &mut Form) {
-    let move_trait = note.is_movable();
-    match move_trait {
-        Some(T) => {
-            T.move_form(i[i.len() - 1].0, i[i.len() - 1].1);
+// Searches the topmost movable form containing the point, below `root`.
+// Children are checked before their parent and later siblings before
+// earlier ones.
+fn find_movable(root: &mut Form, x: i32, y: i32, path: &mut Vec<usize>) -> bool {
+    let children: Vec<&mut Form> = root.get_children().collect();
+    for (i, child) in children.into_iter().enumerate().rev() {
+        if !child.get_bounding_box().is_in_bound(x, y) {
+            continue;
         }
-        None => {
-            note.get_bounding_box()
+
+        path.push(i);
+        if find_movable(child, x, y, path) || child.is_movable() {
+            return true;
         }
+        path.pop();
     }
-}*/
-
-fn get_square_distance(x1: i32, y1: i32, x2: i32, y2: i32) -> i32 {
-    // let x1 = x1 as i32;
-    // let y1 = y1 as i32;
-    // let x2 = x2 as i32;
-    // let y2 = y2 as i32;
-
-    let x = (x1 - x2) * (x1 - x2);
-    let y = (y1 - y2) * (y1 - y2);
-
-    let tmp = (x + y) as u32;
-    //sqrt(tmp)
-    isqrt(tmp) as i32
+    false
 }
-
-// fn to_move mit letzten x Touches --> determine if bounding_box of first touch is to be moved.
-
-// fn check_overlap --> check for other elements
@@ -68,7 +68,7 @@ pub fn print(args: fmt::Arguments) {
 
 static mut STDOUT_BUFFER: ([u8; 100], usize) = ([0; 100], 0);
 
-struct Stdout;
+pub struct Stdout;
 
 impl fmt::Write for Stdout {
     fn write_str(&mut self, s: &str) -> ::core::fmt::Result {
@@ -0,0 +1,120 @@
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+
+use draw::Color;
+use draw::DrawState;
+use draw::DrawTarget;
+use draw::PixelFormat;
+use simulator::png;
+
+/// A draw target that keeps the frame as RGBA pixels in memory.
+pub struct MemoryBuffer {
+    width: i32,
+    height: i32,
+    pixels: Vec<Color>,
+    // the colors are reduced to this format, like the display would show them
+    format: PixelFormat,
+    state: DrawState,
+}
+
+impl MemoryBuffer {
+    pub fn new(width: i32, height: i32) -> MemoryBuffer {
+        MemoryBuffer::with_format(width, height, PixelFormat::Argb8888)
+    }
+
+    pub fn with_format(width: i32, height: i32, format: PixelFormat) -> MemoryBuffer {
+        MemoryBuffer {
+            width: width,
+            height: height,
+            pixels: vec![Color::rgba(0, 0, 0, 0); (width * height) as usize],
+            format: format,
+            state: DrawState::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for pixel in &mut self.pixels {
+            *pixel = Color::rgba(0, 0, 0, 0);
+        }
+    }
+
+    /// Returns the frame as RGBA bytes, row by row.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(self.pixels.len() * 4);
+        for pixel in &self.pixels {
+            res.push(pixel.red);
+            res.push(pixel.green);
+            res.push(pixel.blue);
+            res.push(pixel.alpha);
+        }
+        res
+    }
+
+    /// Returns the frame as RGB bytes like the LTDC shows it, i.e. composed
+    /// onto the black background.
+    pub fn to_rgb(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(self.pixels.len() * 3);
+        for pixel in &self.pixels {
+            let alpha = pixel.alpha as u32;
+            res.push((pixel.red as u32 * alpha / 255) as u8);
+            res.push((pixel.green as u32 * alpha / 255) as u8);
+            res.push((pixel.blue as u32 * alpha / 255) as u8);
+        }
+        res
+    }
+
+    pub fn write_ppm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        write!(file, "P6\n{} {}\n255\n", self.width, self.height)?;
+        file.write_all(&self.to_rgb())
+    }
+
+    pub fn write_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        let data = png::encode(self.width as u32, self.height as u32, &self.to_rgba());
+        file.write_all(&data)
+    }
+
+    /// Writes a PNG if the file name ends with `.png`, a PPM otherwise.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let is_png = match path.as_ref().extension() {
+            Some(ext) => ext == "png",
+            None => false,
+        };
+
+        if is_png {
+            self.write_png(path)
+        } else {
+            self.write_ppm(path)
+        }
+    }
+}
+
+impl DrawTarget for MemoryBuffer {
+    fn width(&self) -> i32 {
+        self.width
+    }
+
+    fn height(&self) -> i32 {
+        self.height
+    }
+
+    fn state(&mut self) -> &mut DrawState {
+        &mut self.state
+    }
+
+    fn put_pixel(&mut self, x: i32, y: i32, color: Color) -> () {
+        let color = match self.format {
+            PixelFormat::Argb8888 => color,
+            format => format.decode(format.encode(color)),
+        };
+        let width = self.width;
+        self.pixels[(y * width + x) as usize] = color;
+    }
+
+    fn get_pixel(&self, x: i32, y: i32) -> Color {
+        self.pixels[(y * self.width + x) as usize]
+    }
+}
@@ -0,0 +1,187 @@
+//! Host-side simulator for the STM32F7 Discovery board.
+//!
+//! The display is replaced by a `MemoryBuffer` with the size and pixel format
+//! of the current display and the touch controller by a `Touchscreen` that
+//! is fed from code. Frames can be written as PPM or PNG files.
+
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::process;
+
+use application::app::App;
+use application::view::View;
+use demo;
+use draw::DisplayInfo;
+use draw::PixelFormat;
+use draw::display;
+use input::InputSource;
+use input::replay;
+use input::replay::Replay;
+
+mod memory_buffer;
+mod png;
+pub mod snapshot;
+mod touch;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::memory_buffer::MemoryBuffer;
+pub use self::touch::Touchscreen;
+
+// Simulated time that passes for each call of `step`, in ticks (ms).
+const TICKS_PER_STEP: usize = 10;
+
+pub struct Simulator {
+    pub display: MemoryBuffer,
+    pub touch: Touchscreen,
+    ticks: usize,
+}
+
+impl Simulator {
+    /// A simulator of the current display, see `draw::display`.
+    pub fn new() -> Simulator {
+        Simulator::with_display(display::current())
+    }
+
+    pub fn with_display(info: DisplayInfo) -> Simulator {
+        Simulator {
+            display: MemoryBuffer::with_format(info.width(), info.height(), info.format()),
+            touch: Touchscreen::new(),
+            ticks: 0,
+        }
+    }
+
+    pub fn with_size(width: i32, height: i32) -> Simulator {
+        Simulator {
+            display: MemoryBuffer::new(width, height),
+            touch: Touchscreen::new(),
+            ticks: 0,
+        }
+    }
+
+    pub fn get_ticks(&self) -> usize {
+        self.ticks
+    }
+
+    /// Runs one iteration of the main loop of the board with the input of
+    /// the simulated touchscreen.
+    pub fn step(&mut self, app: &mut App) {
+        self.ticks += TICKS_PER_STEP;
+        let touches = self.touch.poll(self.ticks);
+        app.update(self.ticks, &touches);
+        app.render(&mut self.display);
+    }
+
+    /// Steps until the replay is finished and returns the number of steps.
+    pub fn replay(&mut self, app: &mut App, replay: &mut Replay) -> usize {
+        let mut steps = 0;
+        loop {
+            self.ticks += TICKS_PER_STEP;
+            let touches = replay.poll(self.ticks);
+            app.update(self.ticks, &touches);
+            app.render(&mut self.display);
+            steps += 1;
+
+            if replay.is_finished(self.ticks) {
+                return steps;
+            }
+        }
+    }
+}
+
+fn parse_size(size: &str) -> Option<(i32, i32)> {
+    let mut parts = size.split('x').map(|part| part.trim().parse::<i32>());
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(width)), Some(Ok(height)), None) if width > 0 && height > 0 => {
+            Some((width, height))
+        }
+        _ => None,
+    }
+}
+
+pub fn load_replay(path: &str) -> Result<Replay, String> {
+    let mut text = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut text))
+        .map_err(|err| err.to_string())?;
+
+    replay::parse(&text)
+        .map(Replay::new)
+        .map_err(|err| err.to_string())
+}
+
+fn view_by_name(name: &str) -> Option<View> {
+    match name {
+        "languages" => Some(demo::view_languages(demo::settings())),
+        "skins" => Some(demo::view_skins()),
+        "settings" => Some(demo::view_settings(demo::settings())),
+        "controls" => Some(demo::view_controls(demo::settings())),
+        "keyboard" => Some(demo::view_keyboard(demo::settings())),
+        "images" => Some(demo::view_images()),
+        "about" => Some(demo::view_about()),
+        _ => None,
+    }
+}
+
+/// Renders a demo view, usage:
+/// `guiframework [--size WxH] [view] [output.ppm|output.png] [x,y | replay.txt]...`.
+/// Every given point is pressed and released for one step and every replay
+/// file is played back before the frame is written. Without a size the
+/// views are laid out for the F746 Discovery.
+pub fn run() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    if args.get(0).map(|s| s.as_str()) == Some("--size") {
+        match args.get(1).and_then(|size| parse_size(size)) {
+            Some((width, height)) => {
+                display::set_current(DisplayInfo::new(width, height, PixelFormat::Argb8888));
+            }
+            None => {
+                println!("the size has to be given as WIDTHxHEIGHT");
+                process::exit(1);
+            }
+        }
+        args.drain(..2);
+    }
+    let name = args.get(0).map(|s| s.as_str()).unwrap_or("skins");
+    let output = args.get(1).map(|s| s.as_str()).unwrap_or("frame.ppm");
+
+    let view = match view_by_name(name) {
+        Some(view) => view,
+        None => {
+            println!("unknown view '{}', use languages, skins, settings, controls, keyboard, images or about",
+                     name);
+            process::exit(1);
+        }
+    };
+
+    let mut simulator = Simulator::new();
+    let mut app = App::new(view);
+    app.render(&mut simulator.display);
+
+    for point in args.iter().skip(2) {
+        let mut coords = point.split(',').map(|c| c.trim().parse::<i32>());
+        match (coords.next(), coords.next()) {
+            (Some(Ok(x)), Some(Ok(y))) => {
+                simulator.touch.press(x, y);
+                simulator.step(&mut app);
+                simulator.touch.release_all();
+                simulator.step(&mut app);
+            }
+            _ => {
+                match load_replay(point) {
+                    Ok(mut replay) => {
+                        simulator.replay(&mut app, &mut replay);
+                    }
+                    Err(err) => println!("ignoring '{}': {}", point, err),
+                }
+            }
+        }
+    }
+
+    if let Err(err) = simulator.display.save(output) {
+        println!("could not write {}: {}", output, err);
+        process::exit(1);
+    }
+}
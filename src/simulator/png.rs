@@ -0,0 +1,97 @@
+// Minimal PNG encoder, the image data is stored in uncompressed deflate
+// blocks so no compression library is needed.
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+// Maximum length of a stored deflate block.
+const MAX_BLOCK: usize = 0xFFFF;
+
+/// Encodes 8 bit RGBA pixel data as PNG.
+pub fn encode(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    assert_eq!(rgba.len(), (width * height * 4) as usize);
+
+    let mut header = Vec::new();
+    push_u32(&mut header, width);
+    push_u32(&mut header, height);
+    // bit depth 8, color type RGBA, default compression, filter and interlacing
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    // every scanline starts with its filter type, we always use none
+    let stride = (width * 4) as usize;
+    let mut raw = Vec::with_capacity((stride + 1) * height as usize);
+    for line in rgba.chunks(stride) {
+        raw.push(0);
+        raw.extend_from_slice(line);
+    }
+
+    let mut res = Vec::new();
+    res.extend_from_slice(&SIGNATURE);
+    write_chunk(&mut res, b"IHDR", &header);
+    write_chunk(&mut res, b"IDAT", &zlib_stored(&raw));
+    write_chunk(&mut res, b"IEND", &[]);
+    res
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    // deflate with 32K window, no preset dictionary, fastest compression
+    let mut res = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        res.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        res.push(if last { 1 } else { 0 });
+        res.push(len as u8);
+        res.push((len >> 8) as u8);
+        res.push(!len as u8);
+        res.push((!len >> 8) as u8);
+        res.extend_from_slice(block);
+    }
+
+    push_u32(&mut res, adler32(data));
+    res
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    push_u32(out, data.len() as u32);
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    push_u32(out, crc);
+}
+
+fn push_u32(out: &mut Vec<u8>, value: u32) {
+    out.push((value >> 24) as u8);
+    out.push((value >> 16) as u8);
+    out.push((value >> 8) as u8);
+    out.push(value as u8);
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            if crc & 1 != 0 {
+                crc = (crc >> 1) ^ 0xEDB8_8320;
+            } else {
+                crc >>= 1;
+            }
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let mut a = 1u32;
+    let mut b = 0u32;
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
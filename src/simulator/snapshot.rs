@@ -0,0 +1,180 @@
+//! Golden-image comparison of rendered frames.
+//!
+//! References live in `snapshots/<name>.ppm`. A missing reference fails the
+//! test, `UPDATE_SNAPSHOTS=1` records it and re-records all others, an empty
+//! value or `0` leaves them as they are. On a mismatch the actual frame and
+//! a diff image are written to `target/snapshots/` and the test fails.
+
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::path::PathBuf;
+
+use draw::DrawTarget;
+use simulator::MemoryBuffer;
+
+/// An RGB image as stored in the reference files.
+struct Image {
+    width: i32,
+    height: i32,
+    data: Vec<u8>,
+}
+
+fn reference_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("snapshots")
+}
+
+fn failure_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target").join("snapshots")
+}
+
+// Whether the value of a flag variable turns the flag on.
+fn is_set(value: &str) -> bool {
+    !value.is_empty() && value != "0"
+}
+
+pub fn assert_snapshot(name: &str, frame: &MemoryBuffer) {
+    let path = reference_dir().join(format!("{}.ppm", name));
+    let update = env::var("UPDATE_SNAPSHOTS").map(|value| is_set(&value)).unwrap_or(false);
+
+    if !update && !path.exists() {
+        panic!("snapshot {}: no reference at {}, run with UPDATE_SNAPSHOTS=1 to record it",
+               name,
+               path.display());
+    }
+
+    if update {
+        fs::create_dir_all(reference_dir()).unwrap();
+        frame.write_ppm(&path).unwrap();
+        println!("recorded snapshot {}", path.display());
+        return;
+    }
+
+    let expected = read_ppm(&path).unwrap();
+    let actual = Image {
+        width: frame.width(),
+        height: frame.height(),
+        data: frame.to_rgb(),
+    };
+
+    if expected.width != actual.width || expected.height != actual.height {
+        panic!("snapshot {}: expected a {}x{} frame, got {}x{}",
+               name,
+               expected.width,
+               expected.height,
+               actual.width,
+               actual.height);
+    }
+
+    let differences = count_differences(&expected, &actual);
+    if differences == 0 {
+        return;
+    }
+
+    fs::create_dir_all(failure_dir()).unwrap();
+    let actual_path = failure_dir().join(format!("{}.actual.ppm", name));
+    let diff_path = failure_dir().join(format!("{}.diff.ppm", name));
+    write_ppm(&actual_path, &actual).unwrap();
+    write_ppm(&diff_path, &diff_image(&expected, &actual)).unwrap();
+
+    panic!("snapshot {}: {} pixels differ, see {}",
+           name,
+           differences,
+           diff_path.display());
+}
+
+fn count_differences(expected: &Image, actual: &Image) -> usize {
+    expected
+        .data
+        .chunks(3)
+        .zip(actual.data.chunks(3))
+        .filter(|&(e, a)| e != a)
+        .count()
+}
+
+// Differing pixels are red, equal ones are a dimmed gray of the reference.
+fn diff_image(expected: &Image, actual: &Image) -> Image {
+    let mut data = Vec::with_capacity(expected.data.len());
+    for (e, a) in expected.data.chunks(3).zip(actual.data.chunks(3)) {
+        if e != a {
+            data.extend_from_slice(&[255, 0, 0]);
+        } else {
+            let gray = ((e[0] as u32 + e[1] as u32 + e[2] as u32) / 12) as u8;
+            data.extend_from_slice(&[gray, gray, gray]);
+        }
+    }
+
+    Image {
+        width: expected.width,
+        height: expected.height,
+        data: data,
+    }
+}
+
+fn write_ppm(path: &PathBuf, image: &Image) -> io::Result<()> {
+    use std::io::Write;
+
+    let mut file = File::create(path)?;
+    write!(file, "P6\n{} {}\n255\n", image.width, image.height)?;
+    file.write_all(&image.data)
+}
+
+fn read_ppm(path: &PathBuf) -> io::Result<Image> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+
+    // header: magic, width, height and maximum value separated by whitespace
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while fields.len() < 4 {
+        while pos < bytes.len() && (bytes[pos] as char).is_whitespace() {
+            pos += 1;
+        }
+        let start = pos;
+        while pos < bytes.len() && !(bytes[pos] as char).is_whitespace() {
+            pos += 1;
+        }
+        if start == pos {
+            return Err(invalid("truncated header"));
+        }
+        fields.push(String::from_utf8_lossy(&bytes[start..pos]).into_owned());
+    }
+    // exactly one whitespace character separates header and data
+    pos += 1;
+
+    if fields[0] != "P6" || fields[3] != "255" {
+        return Err(invalid("only binary 8 bit PPM files are supported"));
+    }
+    let width = fields[1].parse::<i32>().map_err(|_| invalid("invalid width"))?;
+    let height = fields[2].parse::<i32>().map_err(|_| invalid("invalid height"))?;
+
+    let len = (width * height * 3) as usize;
+    if bytes.len() < pos + len {
+        return Err(invalid("truncated image data"));
+    }
+
+    Ok(Image {
+        width: width,
+        height: height,
+        data: bytes[pos..pos + len].to_vec(),
+    })
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_set;
+
+    #[test]
+    fn update_flag() {
+        assert!(is_set("1"));
+        assert!(is_set("yes"));
+        assert!(!is_set(""));
+        assert!(!is_set("0"));
+    }
+}
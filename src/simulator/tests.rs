@@ -0,0 +1,988 @@
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use application::app;
+use application::app::App;
+use application::view::View;
+use demo;
+use draw;
+use draw::BlendMode;
+use draw::Color;
+use draw::display;
+use draw::PixelFormat;
+use action::event::Event;
+use font;
+use font::layout::HorizontalAlign;
+use font::layout::VerticalAlign;
+use forms::button::Button;
+use forms::keyboard::Keyboard;
+use forms::keyboard::KeyboardLayout;
+use forms::progress_bar::ProgressBar;
+use forms::radio_group::RadioGroup;
+use forms::slider::Orientation;
+use forms::slider::Slider;
+use forms::text_field::TextField;
+use draw::DrawTarget;
+use forms::form::Form;
+use forms::form::draw_child;
+use forms::image::Image;
+use forms::image::Scaling;
+use forms::label::Label;
+use image::Bitmap;
+use input::InputSource;
+use input::TouchPoint;
+use input::replay;
+use input::replay::Replay;
+use layout::BorderLayout;
+use layout::HorizontalLayout;
+use layout::MoveBox;
+use layout::ScrollView;
+use layout::VerticalLayout;
+use simulator::MemoryBuffer;
+use simulator::Simulator;
+use simulator::snapshot::assert_snapshot;
+use util::bounding_box::BoundingBox;
+use util::dirty_region::DirtyRegion;
+use util::format;
+use util::layout_funcs::BorderArea;
+
+fn bb(x: i32, y: i32, width: i32, height: i32) -> BoundingBox {
+    BoundingBox {
+        x: x,
+        y: y,
+        width: width,
+        height: height,
+    }
+}
+
+fn render(form: &Form, width: i32, height: i32) -> MemoryBuffer {
+    let mut buffer = MemoryBuffer::new(width, height);
+    form.draw(&mut buffer);
+    buffer
+}
+
+fn button(text: &'static str) -> Box<Button> {
+    let mut button = Button::new(bb(0, 0, 10, 10));
+    button.set_label(Label::new(bb(0, 0, 10, 10), text));
+    Box::new(button)
+}
+
+#[test]
+fn fill_rectangle() {
+    let mut buffer = MemoryBuffer::new(40, 30);
+    draw::fill_rectangle(&mut buffer, 5, 5, 20, 10, Color::from_hex(0xFF8000));
+    assert_snapshot("fill_rectangle", &buffer);
+}
+
+#[test]
+fn button_border() {
+    let mut button = Button::new(bb(10, 10, 100, 60));
+    button.set_border_width(4);
+    assert_snapshot("button_border", &render(&button, 120, 80));
+}
+
+#[test]
+fn label() {
+    let label = Label::new(bb(0, 0, 120, 40), "Label");
+    assert_snapshot("label", &render(&label, 120, 40));
+}
+
+#[test]
+fn label_keeps_its_color_over_the_theme() {
+    let red = Color::from_hex(0xFF0000);
+    let mut dirty = DirtyRegion::new();
+    let mut label = Label::new(bb(0, 0, 120, 40), "Label");
+    label.set_color(red, &mut dirty);
+    assert!(!dirty.is_empty());
+
+    // the app applies its theme before the first draw
+    let mut app = App::new(View::new(Box::new(label)));
+    let mut simulator = Simulator::with_size(120, 40);
+    app.render(&mut simulator.display);
+    let mut red_pixels = 0;
+    for y in 0..40 {
+        for x in 0..120 {
+            if simulator.display.get_pixel(x, y) == red {
+                red_pixels += 1;
+            }
+        }
+    }
+    assert!(red_pixels > 0);
+}
+
+#[test]
+fn horizontal_layout() {
+    let mut layout = HorizontalLayout::new(bb(0, 0, 160, 60));
+    layout.add_form(button("1"));
+    layout.add_form(button("2"));
+    layout.add_form(button("3"));
+    layout.set_proportions(vec![1, 2, 1]);
+    assert_snapshot("horizontal_layout", &render(&layout, 160, 60));
+}
+
+#[test]
+fn vertical_layout() {
+    let mut layout = VerticalLayout::new(bb(0, 0, 60, 160));
+    layout.add_form(button("1"));
+    layout.add_form(button("2"));
+    layout.add_form(button("3"));
+    layout.set_proportions(vec![2, 1, 1]);
+    assert_snapshot("vertical_layout", &render(&layout, 60, 160));
+}
+
+#[test]
+fn border_layout() {
+    let mut layout = BorderLayout::new(bb(0, 0, 150, 90));
+    layout.add_form(button("top"), BorderArea::Top);
+    layout.add_form(button("bottom"), BorderArea::Bottom);
+    layout.add_form(button("left"), BorderArea::Left);
+    layout.add_form(button("right"), BorderArea::Right);
+    layout.add_form(button("center"), BorderArea::Center);
+    assert_snapshot("border_layout", &render(&layout, 150, 90));
+}
+
+#[test]
+fn move_box() {
+    let mut move_box = MoveBox::new(bb(0, 0, 120, 80), false);
+    let mut first = Button::new(bb(10, 10, 40, 30));
+    first.set_movable(true);
+    move_box.add_form(Box::new(first));
+    move_box.add_form(Box::new(Button::new(bb(60, 10, 40, 30))));
+
+    let mut buffer = render(&move_box, 120, 80);
+    let mut dirty = DirtyRegion::new();
+    // moving beyond the box is stopped at its border
+    move_box.get_children().next().unwrap().move_form(30, 100, true, &mut dirty);
+    app::redraw_dirty(&mut move_box, &mut dirty, &mut buffer);
+    assert_snapshot("move_box", &buffer);
+}
+
+fn load(text: &str) -> Replay {
+    Replay::new(replay::parse(text).unwrap())
+}
+
+#[test]
+fn replay_click() {
+    let clicks = Rc::new(Cell::new(0));
+    let mut button = Button::new(bb(20, 20, 80, 40));
+    {
+        let clicks = clicks.clone();
+        button.set_action_on_click(move |_, _| clicks.set(clicks.get() + 1));
+    }
+
+    let mut simulator = Simulator::with_size(120, 80);
+    let mut app = App::new(View::new(Box::new(button)));
+    let mut replay = load(include_str!("../../replays/click_button.txt"));
+    simulator.replay(&mut app, &mut replay);
+
+    // holding the finger down for several samples is still one click
+    assert_eq!(clicks.get(), 1);
+}
+
+#[test]
+fn click_switches_view() {
+    let settings = demo::settings();
+    let mut simulator = Simulator::new();
+    let mut app = App::new(demo::view_languages(settings.clone()));
+    app.render(&mut simulator.display);
+
+    // "Deutsch" is the third of six 60 pixel high buttons in the middle
+    // column
+    simulator.touch.press(240, 150);
+    simulator.step(&mut app);
+    simulator.touch.release_all();
+    simulator.step(&mut app);
+
+    assert_eq!(settings.borrow().language, "Deutsch");
+}
+
+#[test]
+fn jittering_tap_clicks_scrolled_button() {
+    let settings = demo::settings();
+    let mut simulator = Simulator::new();
+    let mut app = App::new(demo::view_languages(settings.clone()));
+    app.render(&mut simulator.display);
+
+    // the list does not scroll by the jitter of the finger on "Deutsch"
+    let id = simulator.touch.press(240, 150);
+    simulator.step(&mut app);
+    for &(x, y) in &[(241, 151), (241, 149), (239, 152)] {
+        simulator.touch.move_to(id, x, y);
+        simulator.step(&mut app);
+    }
+    simulator.touch.release(id);
+    simulator.step(&mut app);
+
+    assert_eq!(settings.borrow().language, "Deutsch");
+}
+
+#[test]
+fn replay_drag() {
+    let mut move_box = MoveBox::new(bb(0, 0, 120, 80), false);
+    let mut first = Button::new(bb(10, 10, 40, 30));
+    first.set_movable(true);
+    move_box.add_form(Box::new(first));
+    move_box.add_form(Box::new(Button::new(bb(60, 10, 40, 30))));
+
+    let mut simulator = Simulator::with_size(120, 80);
+    let mut app = App::new(View::new(Box::new(move_box)));
+    app.render(&mut simulator.display);
+    let mut replay = load(include_str!("../../replays/drag_button.txt"));
+    simulator.replay(&mut app, &mut replay);
+
+    let view = app.get_active_view();
+    let move_box = view.get_children().next().unwrap();
+    let moved = move_box.get_children().next().unwrap().get_bounding_box().clone();
+    assert_eq!((moved.x, moved.y), (40, 30));
+    assert_snapshot("replay_drag", &simulator.display);
+}
+
+#[test]
+fn back_buffer_renders_same_frame() {
+    let display = display::current();
+    let mut direct = MemoryBuffer::new(display.width(), display.height());
+    App::new(demo::view_skins()).render(&mut direct);
+
+    let mut shown = MemoryBuffer::new(display.width(), display.height());
+    let back_buffer = MemoryBuffer::new(display.width(), display.height());
+    App::with_back_buffer(demo::view_skins(), Box::new(back_buffer)).render(&mut shown);
+
+    assert!(direct.to_rgba() == shown.to_rgba());
+}
+
+#[test]
+fn button_pressed() {
+    let mut button = Button::new(bb(10, 10, 100, 60));
+    button.set_action_on_click(|_, _| ());
+
+    let mut simulator = Simulator::with_size(120, 80);
+    let mut app = App::new(View::new(Box::new(button)));
+    simulator.touch.press(50, 40);
+    simulator.step(&mut app);
+    assert_snapshot("button_pressed", &simulator.display);
+
+    simulator.touch.release_all();
+    simulator.step(&mut app);
+    assert_snapshot("button_released", &simulator.display);
+}
+
+fn click(simulator: &mut Simulator, app: &mut App, x: i32, y: i32) {
+    simulator.touch.press(x, y);
+    simulator.step(app);
+    simulator.touch.release_all();
+    simulator.step(app);
+}
+
+#[test]
+fn overlapping_buttons_click_topmost() {
+    let clicks = Rc::new(Cell::new((0, 0)));
+    let mut bottom = Button::new(bb(10, 10, 60, 40));
+    let mut top = Button::new(bb(40, 20, 60, 40));
+    {
+        let clicks = clicks.clone();
+        bottom.set_action_on_click(move |_, _| {
+            let (b, t) = clicks.get();
+            clicks.set((b + 1, t));
+        });
+    }
+    {
+        let clicks = clicks.clone();
+        top.set_action_on_click(move |_, _| {
+            let (b, t) = clicks.get();
+            clicks.set((b, t + 1));
+        });
+    }
+
+    let mut move_box = MoveBox::new(bb(0, 0, 120, 80), false);
+    move_box.add_form(Box::new(bottom));
+    move_box.add_form(Box::new(top));
+
+    let mut simulator = Simulator::with_size(120, 80);
+    let mut app = App::new(View::new(Box::new(move_box)));
+
+    // the top button was added last and covers the bottom one here
+    click(&mut simulator, &mut app, 50, 30);
+    assert_eq!(clicks.get(), (0, 1));
+
+    click(&mut simulator, &mut app, 20, 20);
+    assert_eq!(clicks.get(), (1, 1));
+}
+
+#[test]
+fn click_bubbles_until_stopped() {
+    let outer_clicks = Rc::new(Cell::new(0));
+    let stop = Rc::new(Cell::new(false));
+    let mut outer = Button::new(bb(10, 10, 100, 60));
+    let mut inner = Button::new(bb(10, 10, 100, 60));
+    {
+        let outer_clicks = outer_clicks.clone();
+        outer.set_action_on_click(move |_, _| outer_clicks.set(outer_clicks.get() + 1));
+    }
+    {
+        let stop = stop.clone();
+        inner.set_action_on_click(move |_, ctx| if stop.get() {
+            ctx.stop_propagation();
+        });
+    }
+    outer.set_child(Box::new(inner));
+
+    let mut simulator = Simulator::with_size(120, 80);
+    let mut app = App::new(View::new(Box::new(outer)));
+
+    click(&mut simulator, &mut app, 60, 40);
+    assert_eq!(outer_clicks.get(), 1);
+
+    stop.set(true);
+    click(&mut simulator, &mut app, 60, 40);
+    assert_eq!(outer_clicks.get(), 1);
+}
+
+fn touch(id: u8, x: i32, y: i32) -> TouchPoint {
+    TouchPoint { id: id, x: x, y: y }
+}
+
+// Counts the drag starts, moves and ends the button gets.
+fn count_drags(button: &mut Button) -> Rc<Cell<(i32, i32, i32)>> {
+    let drags = Rc::new(Cell::new((0, 0, 0)));
+    let counter = drags.clone();
+    button.set_event_handler(move |_, event, _| {
+        let (start, moves, end) = counter.get();
+        match *event {
+            Event::DragStart { .. } => counter.set((start + 1, moves, end)),
+            Event::DragMove { .. } => counter.set((start, moves + 1, end)),
+            Event::DragEnd { .. } => counter.set((start, moves, end + 1)),
+            _ => {}
+        }
+    });
+    drags
+}
+
+#[test]
+fn two_finger_drag() {
+    let mut left = Button::new(bb(10, 20, 30, 30));
+    let mut right = Button::new(bb(80, 20, 30, 30));
+    left.set_movable(true);
+    right.set_movable(true);
+    let drags = count_drags(&mut left);
+
+    let mut move_box = MoveBox::new(bb(0, 0, 120, 80), false);
+    move_box.add_form(Box::new(left));
+    move_box.add_form(Box::new(right));
+
+    let mut app = App::new(View::new(Box::new(move_box)));
+
+    // the fingers pass each other closely and fast
+    let steps = [(20, 90), (45, 65), (58, 52), (70, 40)];
+    for (step, &(x1, x2)) in steps.iter().enumerate() {
+        app.update(step * 16, &[touch(0, x1, 30), touch(1, x2, 30)]);
+    }
+    app.update(steps.len() * 16, &[]);
+
+    let mut children = app.get_active_view().get_children();
+    let move_box = children.next().unwrap();
+    let mut buttons = move_box.get_children();
+    assert_eq!(buttons.next().unwrap().get_bounding_box().x, 60);
+    assert_eq!(buttons.next().unwrap().get_bounding_box().x, 30);
+    assert_eq!(drags.get(), (1, 3, 1));
+}
+
+#[test]
+fn lifting_one_finger_keeps_the_other_dragging() {
+    let mut left = Button::new(bb(10, 20, 30, 30));
+    let mut right = Button::new(bb(80, 20, 30, 30));
+    left.set_movable(true);
+    right.set_movable(true);
+    let drags = count_drags(&mut right);
+
+    let mut move_box = MoveBox::new(bb(0, 0, 120, 80), false);
+    move_box.add_form(Box::new(left));
+    move_box.add_form(Box::new(right));
+
+    let mut simulator = Simulator::with_size(120, 80);
+    let mut app = App::new(View::new(Box::new(move_box)));
+
+    let first = simulator.touch.press(20, 30);
+    let second = simulator.touch.press(90, 30);
+    simulator.step(&mut app);
+    simulator.touch.move_to(first, 30, 30);
+    simulator.touch.move_to(second, 80, 30);
+    simulator.step(&mut app);
+
+    // the second finger keeps its id when the first is lifted and drags on
+    simulator.touch.release(first);
+    simulator.step(&mut app);
+    assert_eq!(simulator.touch.poll(0)[0].id, second);
+    for x in &[75, 70, 60] {
+        simulator.touch.move_to(second, *x, 30);
+        simulator.step(&mut app);
+    }
+    assert_eq!(drags.get(), (1, 4, 0));
+    simulator.touch.release_all();
+    simulator.step(&mut app);
+    assert_eq!(drags.get(), (1, 4, 1));
+
+    let mut children = app.get_active_view().get_children();
+    let move_box = children.next().unwrap();
+    let mut buttons = move_box.get_children();
+    assert_eq!(buttons.next().unwrap().get_bounding_box().x, 20);
+    assert_eq!(buttons.next().unwrap().get_bounding_box().x, 50);
+}
+
+#[test]
+fn jittering_tap_clicks_movable_button() {
+    let clicks = Rc::new(Cell::new(0));
+    let mut button = Button::new(bb(20, 20, 80, 40));
+    button.set_movable(true);
+    {
+        let clicks = clicks.clone();
+        button.set_action_on_click(move |_, _| clicks.set(clicks.get() + 1));
+    }
+
+    let mut move_box = MoveBox::new(bb(0, 0, 120, 80), false);
+    move_box.add_form(Box::new(button));
+    let mut simulator = Simulator::with_size(120, 80);
+    let mut app = App::new(View::new(Box::new(move_box)));
+
+    // a resting finger moves by a pixel or two between polls
+    let id = simulator.touch.press(60, 40);
+    simulator.step(&mut app);
+    for &(x, y) in &[(61, 41), (62, 40), (60, 42)] {
+        simulator.touch.move_to(id, x, y);
+        simulator.step(&mut app);
+    }
+    simulator.touch.release(id);
+    simulator.step(&mut app);
+    assert_eq!(clicks.get(), 1);
+
+    let mut children = app.get_active_view().get_children();
+    let move_box = children.next().unwrap();
+    let moved = move_box.get_children().next().unwrap().get_bounding_box().clone();
+    assert_eq!((moved.x, moved.y), (20, 20));
+}
+
+#[test]
+fn scroll_view_flings_and_bounces() {
+    let settings = demo::settings();
+    let mut simulator = Simulator::new();
+    let mut app = App::new(demo::view_languages(settings.clone()));
+
+    // a fast swipe up scrolls past the end of the list
+    simulator.touch.press(240, 250);
+    let mut y = 250;
+    while y > 50 {
+        simulator.step(&mut app);
+        y -= 25;
+        simulator.touch.move_to(0, 240, y);
+    }
+    simulator.touch.release_all();
+    for _ in 0..100 {
+        simulator.step(&mut app);
+    }
+
+    // and springs back to the end, "Rust" is at the bottom of the screen
+    assert_snapshot("scroll_view", &simulator.display);
+
+    // ticks that go backwards, e.g. after the counter wrapped, are no time
+    app.update(0, &[]);
+    simulator.touch.press(240, 240);
+    simulator.step(&mut app);
+    simulator.touch.release_all();
+    simulator.step(&mut app);
+    assert_eq!(settings.borrow().language, "Rust");
+}
+
+#[test]
+fn children_are_clipped_to_parent() {
+    let mut buffer = MemoryBuffer::new(120, 80);
+    let button = Button::new(bb(20, 20, 100, 60));
+    draw_child(&mut buffer, &bb(10, 10, 40, 30), &button);
+
+    // the top left corner of the border is within the parent
+    let white = Color::from_hex(0xFFFFFF);
+    let background = Color::rgba(0, 0, 0, 0);
+    assert_eq!(buffer.get_pixel(30, 20), white);
+    assert_eq!(buffer.get_pixel(20, 30), white);
+    assert_eq!(buffer.get_pixel(60, 20), background);
+    assert_eq!(buffer.get_pixel(20, 45), background);
+
+    // nested clips only leave the intersection
+    buffer.clear();
+    buffer.push_clip(&bb(0, 0, 20, 20));
+    buffer.push_clip(&bb(10, 10, 20, 20));
+    draw::fill_rectangle(&mut buffer, 0, 0, 100, 60, white);
+    buffer.pop_clip();
+    buffer.pop_clip();
+    assert_eq!(buffer.get_pixel(15, 15), white);
+    assert_eq!(buffer.get_pixel(5, 5), background);
+    assert_eq!(buffer.get_pixel(25, 25), background);
+}
+
+#[test]
+fn primitives() {
+    let mut buffer = MemoryBuffer::new(200, 120);
+    let white = Color::from_hex(0xFFFFFF);
+    let red = Color::from_hex(0xFF4020);
+
+    // horizontal and vertical lines used to divide by zero
+    draw::draw_line(&mut buffer, 5, 5, 60, 5, white);
+    draw::draw_line(&mut buffer, 5, 5, 5, 60, white);
+    draw::draw_line(&mut buffer, 10, 10, 60, 40, white);
+    draw::draw_line_aa(&mut buffer, 10, 20, 60, 50, white);
+    draw::draw_thick_line(&mut buffer, 10, 60, 60, 100, 5, red);
+
+    draw::draw_circle(&mut buffer, 90, 30, 20, white);
+    draw::draw_circle_aa(&mut buffer, 90, 30, 14, red);
+    draw::fill_ellipse(&mut buffer, 90, 85, 25, 12, red);
+    draw::draw_ellipse(&mut buffer, 90, 85, 25, 12, white);
+    draw::draw_arc(&mut buffer, 150, 30, 20, 300, 135, 4, red);
+    draw::fill_rounded_rectangle(&mut buffer, 125, 60, 60, 25, 8, red);
+    draw::draw_rounded_rectangle(&mut buffer, 125, 60, 60, 25, 8, white);
+    draw::fill_polygon(&mut buffer, &[(130, 95), (190, 92), (160, 115), (150, 100)], white);
+
+    assert_snapshot("primitives", &buffer);
+}
+
+#[test]
+fn blend_modes() {
+    let mut buffer = MemoryBuffer::new(10, 10);
+    let red = Color::rgb(200, 0, 0);
+    let shadow = Color::rgba(0, 0, 0, 128);
+
+    // a half transparent shadow darkens what is below
+    draw::fill_rectangle(&mut buffer, 0, 0, 9, 9, red);
+    buffer.draw_pixel(1, 1, shadow);
+    assert_eq!(buffer.get_pixel(1, 1), Color::rgb(99, 0, 0));
+
+    // over the transparent background it stays half transparent
+    buffer.clear();
+    buffer.draw_pixel(1, 1, shadow);
+    assert_eq!(buffer.get_pixel(1, 1), shadow);
+
+    buffer.set_blend_mode(BlendMode::Additive);
+    buffer.draw_pixel(2, 2, red);
+    buffer.draw_pixel(2, 2, red);
+    assert_eq!(buffer.get_pixel(2, 2), Color::rgb(255, 0, 0));
+
+    // replacing erases, which drawing transparent pixels over does not
+    buffer.set_blend_mode(BlendMode::SourceOver);
+    buffer.draw_pixel(2, 2, Color::rgba(0, 0, 0, 0));
+    assert_eq!(buffer.get_pixel(2, 2), Color::rgb(255, 0, 0));
+    buffer.set_blend_mode(BlendMode::Replace);
+    buffer.draw_pixel(2, 2, Color::rgba(0, 0, 0, 0));
+    assert_eq!(buffer.get_pixel(2, 2), Color::rgba(0, 0, 0, 0));
+}
+
+#[test]
+fn pixel_formats() {
+    // the simulator shows the colors like the display would
+    let gray = Color::rgb(0x40, 0x41, 0x42);
+    let mut simulator = Simulator::with_display(display::DISCOVERY_F746);
+    assert_eq!(simulator.display.width(), 480);
+    simulator.display.draw_pixel(0, 0, gray);
+    assert_eq!(simulator.display.get_pixel(0, 0), Color::rgb(0x44, 0x44, 0x44));
+}
+
+#[test]
+fn skins_switch_theme() {
+    let mut simulator = Simulator::new();
+    let mut app = App::new(demo::view_skins());
+    app.render(&mut simulator.display);
+    assert_eq!(simulator.display.get_pixel(5, 5), Color::rgba(0, 0, 0, 0));
+
+    // the last skin is the clippy theme
+    click(&mut simulator, &mut app, 240, 245);
+    assert_eq!(app.get_theme().name, "Clippy");
+    assert_eq!(simulator.display.get_pixel(5, 5), Color::from_hex(0xFFFFCC));
+    assert_snapshot("skins_clippy", &simulator.display);
+
+    // views shown later get the theme as well
+    app.set_active_view(demo::view_about());
+    app.render(&mut simulator.display);
+    assert_eq!(simulator.display.get_pixel(5, 5), Color::from_hex(0xFFFFCC));
+}
+
+#[test]
+fn fonts() {
+    let tiny = font::bdf::parse(font::bdf::tests::TINY).unwrap();
+    let red = Color::rgb(255, 0, 0);
+    let mut buffer = MemoryBuffer::new(20, 10);
+    font::draw_text(&mut buffer, 0, 0, "+,", &tiny, 2, red);
+    // the center of the plus, scaled by two
+    assert_eq!(buffer.get_pixel(2, 2), red);
+    assert_eq!(buffer.get_pixel(3, 3), red);
+    assert_eq!(buffer.get_pixel(0, 0), Color::rgba(0, 0, 0, 0));
+    // the comma reaches below the baseline at y 6
+    assert_eq!(buffer.get_pixel(8, 7), red);
+}
+
+#[test]
+fn text_layout() {
+    let mut label = Label::new(bb(0, 0, 80, 60), "Français\nDeutsch Italiano");
+    {
+        let layout = label.get_text_layout();
+        layout.horizontal = HorizontalAlign::Right;
+        layout.vertical = VerticalAlign::Top;
+        layout.wrap = true;
+        layout.line_spacing = 2;
+    }
+    assert_snapshot("text_layout", &render(&label, 81, 61));
+}
+
+#[test]
+fn label_text_changes() {
+    let mut button = Button::new(bb(10, 10, 100, 30));
+    button.set_label(Label::new(bb(0, 0, 10, 10), "0"));
+    let mut dirty = DirtyRegion::new();
+
+    button.set_text(&format::grouped(1234, ' '), &mut dirty);
+    assert_eq!(button.get_label().unwrap().get_text(), "1 234");
+    assert!(!dirty.is_empty());
+
+    // setting the same text again does not redraw
+    dirty.take();
+    button.set_text("1 234", &mut dirty);
+    assert!(dirty.is_empty());
+}
+
+#[test]
+fn image_scaling() {
+    let red = Color::rgb(255, 0, 0);
+    let blue = Color::rgb(0, 0, 255);
+    let key = Color::rgb(255, 0, 255);
+    let bitmap = || Bitmap::new(2, 2, vec![red, blue, key, red]);
+    let transparent = Color::rgba(0, 0, 0, 0);
+
+    // fit keeps the square in the middle of the wide box
+    let mut dirty = DirtyRegion::new();
+    let mut image = Image::new(bb(0, 0, 8, 4), bitmap());
+    image.set_color_key(Some(key), &mut dirty);
+    assert!(!dirty.take().is_empty());
+    let buffer = render(&image, 8, 4);
+    assert_eq!(buffer.get_pixel(1, 0), transparent);
+    assert_eq!(buffer.get_pixel(2, 0), red);
+    assert_eq!(buffer.get_pixel(4, 1), blue);
+    assert_eq!(buffer.get_pixel(2, 2), transparent);
+    assert_eq!(buffer.get_pixel(5, 3), red);
+
+    image.set_scaling(Scaling::Stretch, &mut dirty);
+    assert!(!dirty.take().is_empty());
+    let buffer = render(&image, 8, 4);
+    assert_eq!(buffer.get_pixel(0, 0), red);
+    assert_eq!(buffer.get_pixel(7, 1), blue);
+
+    // fill cuts off the top and bottom quarter
+    image.set_scaling(Scaling::Fill, &mut dirty);
+    let buffer = render(&image, 8, 4);
+    assert_eq!(buffer.get_pixel(0, 1), red);
+    assert_eq!(buffer.get_pixel(7, 2), red);
+
+    image.set_scaling(Scaling::None, &mut dirty);
+    let buffer = render(&image, 8, 4);
+    assert_eq!(buffer.get_pixel(3, 1), red);
+    assert_eq!(buffer.get_pixel(2, 1), transparent);
+
+    // the same settings again leave the image as it is
+    dirty.take();
+    image.set_scaling(Scaling::None, &mut dirty);
+    image.set_color_key(Some(key), &mut dirty);
+    assert!(dirty.is_empty());
+}
+
+#[test]
+fn images_view() {
+    let mut simulator = Simulator::new();
+    let mut app = App::new(demo::view_images());
+    app.render(&mut simulator.display);
+    assert_snapshot("images", &simulator.display);
+}
+
+#[test]
+fn rectangles_match_pixels() {
+    let background = Color::rgba(20, 200, 60, 160);
+    let color = Color::rgba(255, 40, 10, 100);
+    let bitmap = Bitmap::new(3,
+                             2,
+                             vec![color, Color::rgba(0, 0, 0, 0), Color::rgb(0, 0, 255), color,
+                                  Color::rgba(90, 90, 90, 255), Color::rgba(10, 0, 200, 30)]);
+    let bitmap_area = bb(18, 8, 3, 2);
+
+    // the whole rectangle calls and the pixels drawn one by one have to give
+    // the same result, including clipping and blending
+    for &blend_mode in &[BlendMode::SourceOver, BlendMode::Replace, BlendMode::Additive] {
+        let mut fast = MemoryBuffer::with_format(20, 10, PixelFormat::Argb4444);
+        let mut slow = MemoryBuffer::with_format(20, 10, PixelFormat::Argb4444);
+        for buffer in vec![&mut fast, &mut slow] {
+            draw::fill_rectangle(buffer, 0, 0, 19, 9, background);
+            buffer.set_blend_mode(blend_mode);
+            buffer.push_clip(&bb(2, 2, 30, 6));
+        }
+
+        fast.fill_rect(&bb(-5, 0, 12, 20), color);
+        fast.draw_bitmap(bitmap_area.x, bitmap_area.y, &bitmap);
+        for y in 0..20 {
+            for x in -5..7 {
+                slow.draw_pixel(x, y, color);
+            }
+        }
+        for y in 0..bitmap_area.height {
+            for x in 0..bitmap_area.width {
+                slow.draw_pixel(bitmap_area.x + x, bitmap_area.y + y, bitmap.get_pixel(x, y));
+            }
+        }
+
+        for y in 0..10 {
+            for x in 0..20 {
+                assert_eq!(fast.get_pixel(x, y),
+                           slow.get_pixel(x, y),
+                           "{:?} at {}, {}",
+                           blend_mode,
+                           x,
+                           y);
+            }
+        }
+        // outside of the clip rectangle the background is left as it is
+        assert!(fast.get_pixel(3, 3) != fast.get_pixel(3, 1));
+        assert_eq!(fast.get_pixel(3, 1), fast.get_pixel(12, 5));
+    }
+}
+
+// The blender of the DMA2D as the reference manual describes it, the pixels
+// are converted to ARGB8888 before and back to the target's format after.
+fn dma2d_over(format: PixelFormat, fg: Color, bg: Color) -> Color {
+    let bg = format.decode(format.encode(bg));
+    let (fg_alpha, bg_alpha) = (fg.alpha as u32, bg.alpha as u32);
+    let mult = fg_alpha * bg_alpha / 255;
+    let out = fg_alpha + bg_alpha - mult;
+    if out == 0 {
+        // the manual leaves this open, nothing is visible of either
+        return bg;
+    }
+    let mix = |fg: u8, bg: u8| {
+        ((fg as u32 * fg_alpha + bg as u32 * bg_alpha - bg as u32 * mult) / out) as u8
+    };
+    let color = Color::rgba(mix(fg.red, bg.red),
+                            mix(fg.green, bg.green),
+                            mix(fg.blue, bg.blue),
+                            out as u8);
+    format.decode(format.encode(color))
+}
+
+#[test]
+fn blending_matches_dma2d() {
+    let below = [Color::rgb(200, 30, 90), Color::rgba(20, 200, 60, 160), Color::rgba(250, 5, 0, 1)];
+    for &format in &[PixelFormat::Argb8888, PixelFormat::Rgb565, PixelFormat::Argb4444] {
+        for &bg in &below {
+            // every alpha of the bitmap drawn over the same background
+            let pixels = (0..256).map(|alpha| Color::rgba(alpha as u8, 120, 255, alpha as u8));
+            let bitmap = Bitmap::new(256, 1, pixels.collect());
+            let mut buffer = MemoryBuffer::with_format(256, 1, format);
+            draw::fill_rectangle(&mut buffer, 0, 0, 255, 0, bg);
+            let bg = buffer.get_pixel(0, 0);
+            buffer.draw_bitmap(0, 0, &bitmap);
+
+            for x in 0..256 {
+                let fg = bitmap.get_pixel(x, 0);
+                assert_eq!(buffer.get_pixel(x, 0),
+                           dma2d_over(format, fg, bg),
+                           "{:?} over {:?}",
+                           fg,
+                           bg);
+            }
+        }
+    }
+}
+
+#[test]
+fn toggles() {
+    let settings = demo::settings();
+    let mut simulator = Simulator::new();
+    let mut app = App::new(demo::view_settings(settings.clone()));
+    app.render(&mut simulator.display);
+
+    // the languages are on the left, the checkbox and switch rows on the
+    // right
+    click(&mut simulator, &mut app, 60, 112);
+    assert_eq!(settings.borrow().language, "Deutsch");
+    click(&mut simulator, &mut app, 230, 34);
+    assert!(!settings.borrow().auto_capitalization);
+    click(&mut simulator, &mut app, 430, 170);
+    assert!(settings.borrow().sound_on_keypress);
+
+    // the knob needs a few steps to slide over
+    for _ in 0..20 {
+        simulator.step(&mut app);
+    }
+    assert_snapshot("settings_toggles", &simulator.display);
+
+    let selected = Rc::new(Cell::new(0));
+    let mut radio = RadioGroup::new(bb(0, 0, 200, 90), &["QWERTY", "QWERTZ", "AZERTY"], 0);
+    {
+        let selected = selected.clone();
+        radio.set_action_on_change(move |radio, _| selected.set(radio.get_selected()));
+    }
+    let mut app = App::new(View::new(Box::new(radio)));
+    app.render(&mut simulator.display);
+
+    click(&mut simulator, &mut app, 50, 75);
+    assert_eq!(selected.get(), 2);
+
+    // selecting the same option again is no change
+    selected.set(5);
+    click(&mut simulator, &mut app, 50, 75);
+    assert_eq!(selected.get(), 5);
+
+    // the option the finger went down on is selected
+    simulator.touch.press(50, 45);
+    simulator.step(&mut app);
+    simulator.touch.move_to(0, 50, 75);
+    simulator.step(&mut app);
+    simulator.touch.release_all();
+    simulator.step(&mut app);
+    assert_eq!(selected.get(), 1);
+}
+
+#[test]
+fn slider_and_progress_bar() {
+    let settings = demo::settings();
+    let mut simulator = Simulator::new();
+    let mut app = App::new(demo::view_controls(settings.clone()));
+    app.render(&mut simulator.display);
+
+    // the volume slider is right of its label in the middle one of three
+    // rows, dragging the knob to the left end turns it down
+    let (x, y) = (285, 135);
+    simulator.touch.press(x, y);
+    simulator.step(&mut app);
+    for step in 1..13 {
+        simulator.touch.move_to(0, x - step * 10, y);
+        simulator.step(&mut app);
+    }
+    assert_eq!(settings.borrow().volume, 0);
+    simulator.touch.release_all();
+    simulator.step(&mut app);
+
+    // pressing the track jumps there, in steps of ten
+    click(&mut simulator, &mut app, 324, 81);
+    assert_eq!(settings.borrow().brightness, 80);
+    assert_snapshot("controls", &simulator.display);
+
+    let mut dirty = DirtyRegion::new();
+    let mut slider = Slider::new(bb(0, 0, 120, 20), 0, 10, 0);
+    slider.set_step(4, &mut dirty);
+    assert!(!dirty.is_empty());
+    slider.set_value(7, &mut dirty);
+    assert_eq!(slider.get_value(), 8);
+    slider.set_value(11, &mut dirty);
+    assert_eq!(slider.get_value(), 10);
+    dirty.take();
+    slider.set_orientation(Orientation::Vertical, &mut dirty);
+    assert!(!dirty.is_empty());
+    assert_eq!(Slider::new(bb(0, 0, 120, 20), 0, 10, 7).with_step(4).get_value(), 8);
+
+    // a dragged slider stays within its parent
+    slider.set_outer_bounding_box(bb(0, 0, 150, 40));
+    slider.move_form(100, 100, true, &mut dirty);
+    let moved = slider.get_bounding_box().clone();
+    assert_eq!((moved.x, moved.y), (30, 20));
+
+    let mut bar = ProgressBar::new(bb(0, 0, 40, 10), 200);
+    bar.set_value(100, &mut dirty);
+    let buffer = render(&bar, 50, 20);
+    assert!(buffer.get_pixel(15, 5) != Color::rgba(0, 0, 0, 0));
+    assert_eq!(buffer.get_pixel(25, 5), Color::rgba(0, 0, 0, 0));
+
+    // the block of an indeterminate bar moves on with the ticks
+    bar.set_indeterminate(true);
+    bar.tick(0, &mut dirty);
+    bar.tick(750, &mut dirty);
+    let buffer = render(&bar, 50, 20);
+    assert_eq!(buffer.get_pixel(5, 5), Color::rgba(0, 0, 0, 0));
+    assert!(buffer.get_pixel(20, 5) != Color::rgba(0, 0, 0, 0));
+}
+
+#[test]
+fn slider_in_scroll_view_does_not_scroll() {
+    let value = Rc::new(Cell::new(50));
+    let mut slider = Slider::new(bb(0, 0, 10, 10), 0, 100, 50);
+    {
+        let value = value.clone();
+        slider.set_action_on_change(move |slider, _| value.set(slider.get_value()));
+    }
+    let mut content = VerticalLayout::new(bb(0, 0, 200, 400));
+    content.add_form(Box::new(slider));
+    content.add_form(Box::new(HorizontalLayout::new(bb(0, 0, 10, 10))));
+    let mut scroll_view = ScrollView::new(bb(0, 0, 200, 100));
+    scroll_view.set_child(Box::new(content));
+
+    let mut simulator = Simulator::with_size(200, 100);
+    let mut app = App::new(View::new(Box::new(scroll_view)));
+
+    // the finger on the slider moves the knob, not the content
+    let id = simulator.touch.press(100, 80);
+    simulator.step(&mut app);
+    for step in 1..10 {
+        simulator.touch.move_to(id, 100 - step * 10, 80 - step * 6);
+        simulator.step(&mut app);
+    }
+    simulator.touch.release(id);
+    simulator.step(&mut app);
+    assert_eq!(value.get(), 0);
+
+    let mut children = app.get_active_view().get_children();
+    let scroll_view = children.next().unwrap();
+    let content = scroll_view.get_children().next().unwrap();
+    assert_eq!(content.get_bounding_box().y, 0);
+}
+
+#[test]
+fn keyboard_types_into_text_field() {
+    let text = Rc::new(RefCell::new(String::new()));
+    let mut text_field = TextField::new(bb(0, 0, 10, 10));
+    {
+        let text = text.clone();
+        text_field.set_action_on_change(move |field, _| {
+            *text.borrow_mut() = String::from(field.get_text());
+        });
+    }
+    let mut keyboard = Keyboard::new(bb(0, 0, 10, 10), KeyboardLayout::Qwerty);
+    keyboard.set_auto_capitalization(true);
+    keyboard.set_double_space_period(true);
+
+    // the field is 68 pixels high, the four rows of keys below 51 each
+    let mut f = VerticalLayout::new(bb(0, 0, 480, 272));
+    f.add_form(Box::new(text_field));
+    f.add_form(Box::new(keyboard));
+    f.set_proportions(vec![1, 3]);
+    let mut app = App::new(View::new(Box::new(f)));
+    let mut simulator = Simulator::new();
+    app.render(&mut simulator.display);
+
+    // the enter key goes nowhere before the field is focused
+    click(&mut simulator, &mut app, 440, 246);
+    assert_eq!(*text.borrow(), "");
+    click(&mut simulator, &mut app, 240, 34);
+
+    // h, i, two spaces, x and backspace
+    for &(x, y) in &[(293, 144), (360, 93), (240, 246), (240, 246), (144, 195), (444, 195)] {
+        click(&mut simulator, &mut app, x, y);
+    }
+    assert_eq!(*text.borrow(), "Hi. ");
+
+    // the 1 on the symbols page
+    click(&mut simulator, &mut app, 40, 246);
+    click(&mut simulator, &mut app, 24, 93);
+    assert_eq!(*text.borrow(), "Hi. 1");
+
+    // the popup of the pressed g, which is not typed when the finger
+    // slides off
+    click(&mut simulator, &mut app, 40, 246);
+    simulator.touch.press(240, 144);
+    simulator.step(&mut app);
+    assert_snapshot("keyboard", &simulator.display);
+    simulator.touch.move_to(0, 240, 30);
+    simulator.step(&mut app);
+    simulator.touch.release_all();
+    simulator.step(&mut app);
+    assert_eq!(*text.borrow(), "Hi. 1");
+}
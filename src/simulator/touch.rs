@@ -0,0 +1,57 @@
+use input::InputSource;
+use input::TouchPoint;
+
+/// In-memory replacement for the FT5336 touch controller.
+///
+/// Contacts stay pressed until they are released again, so every poll
+/// returns the same points like the real controller does for a resting
+/// finger. Every contact keeps its id until it is released, like the touch
+/// ids of the controller.
+pub struct Touchscreen {
+    // id and position
+    contacts: Vec<(u8, i32, i32)>,
+}
+
+impl Touchscreen {
+    pub fn new() -> Touchscreen {
+        Touchscreen { contacts: Vec::new() }
+    }
+
+    /// Adds a contact and returns its id, the lowest one that is free.
+    pub fn press(&mut self, x: i32, y: i32) -> u8 {
+        let id = (0..).find(|&id| self.contacts.iter().all(|&(other, _, _)| other != id)).unwrap();
+        self.contacts.push((id, x, y));
+        id
+    }
+
+    // Moves the contact with the given id, e.g. to simulate a drag.
+    pub fn move_to(&mut self, id: u8, x: i32, y: i32) {
+        if let Some(contact) = self.contacts.iter_mut().find(|contact| contact.0 == id) {
+            *contact = (id, x, y);
+        }
+    }
+
+    /// Lifts the contact with the given id, the others stay.
+    pub fn release(&mut self, id: u8) {
+        self.contacts.retain(|contact| contact.0 != id);
+    }
+
+    pub fn release_all(&mut self) {
+        self.contacts.clear();
+    }
+}
+
+impl InputSource for Touchscreen {
+    fn poll(&mut self, _ticks: usize) -> Vec<TouchPoint> {
+        self.contacts
+            .iter()
+            .map(|&(id, x, y)| {
+                TouchPoint {
+                    id: id,
+                    x: x,
+                    y: y,
+                }
+            })
+            .collect()
+    }
+}
@@ -1,6 +1,8 @@
-use util::sizes;
+use core::cmp::max;
+use core::cmp::min;
 
-#[derive(Clone)]
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BoundingBox {
     pub x: i32,
     pub y: i32,
@@ -13,6 +15,53 @@ impl BoundingBox {
         x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.x < other.x + other.width && other.x < self.x + self.width &&
+        self.y < other.y + other.height && other.y < self.y + self.height
+    }
+
+    pub fn intersection(&self, other: &BoundingBox) -> Option<BoundingBox> {
+        if !self.intersects(other) {
+            return None;
+        }
+
+        let x = max(self.x, other.x);
+        let y = max(self.y, other.y);
+        Some(BoundingBox {
+            x: x,
+            y: y,
+            width: min(self.x + self.width, other.x + other.width) - x,
+            height: min(self.y + self.height, other.y + other.height) - y,
+        })
+    }
+
+    // Smallest box containing both boxes.
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        let x = min(self.x, other.x);
+        let y = min(self.y, other.y);
+        BoundingBox {
+            x: x,
+            y: y,
+            width: max(self.x + self.width, other.x + other.width) - x,
+            height: max(self.y + self.height, other.y + other.height) - y,
+        }
+    }
+
+    // The box including the last row and column, forms draw their outline
+    // there as well (see `draw::draw_rectangle`).
+    pub fn with_outline(&self) -> BoundingBox {
+        BoundingBox {
+            x: self.x,
+            y: self.y,
+            width: self.width + 1,
+            height: self.height + 1,
+        }
+    }
+
     pub fn get_center(&self) -> (i32, i32) {
         (self.x + self.width / 2, self.y + self.height / 2)
     }
@@ -112,3 +161,43 @@ impl BoundingBox {
         (moved_x, moved_y)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bb(x: i32, y: i32, width: i32, height: i32) -> BoundingBox {
+        BoundingBox {
+            x: x,
+            y: y,
+            width: width,
+            height: height,
+        }
+    }
+
+    #[test]
+    fn intersection_and_union() {
+        let a = bb(0, 0, 10, 10);
+        assert!(a.is_in_bound(9, 9));
+        assert!(!a.is_in_bound(10, 9));
+        assert_eq!(a.intersection(&bb(5, 5, 10, 10)), Some(bb(5, 5, 5, 5)));
+        // boxes that only touch do not intersect
+        assert_eq!(a.intersection(&bb(10, 0, 10, 10)), None);
+        assert_eq!(a.union(&bb(20, 5, 5, 10)), bb(0, 0, 25, 15));
+        assert_eq!(a.with_outline(), bb(0, 0, 11, 11));
+    }
+
+    #[test]
+    fn moves_within_outer_box() {
+        let outer = bb(0, 0, 100, 50);
+        let mut inner = bb(10, 10, 20, 20);
+        assert_eq!(inner.move_in_direction(-30, 5, Some(&outer)), (-10, 5));
+        assert_eq!(inner, bb(0, 15, 20, 20));
+        assert_eq!(inner.move_in_direction(0, 40, Some(&outer)), (0, 15));
+        assert_eq!(inner, bb(0, 30, 20, 20));
+
+        // without an outer box the move is not limited
+        assert_eq!(inner.move_in_direction(-30, 0, None), (-30, 0));
+        assert_eq!(inner.x, -30);
+    }
+}
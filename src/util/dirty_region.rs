@@ -0,0 +1,89 @@
+use collections::Vec;
+use core::mem;
+
+use util::bounding_box::BoundingBox;
+
+// More separate rectangles are not worth the overhead of redrawing the
+// intersecting forms for each of them.
+const MAX_RECTS: usize = 8;
+
+/// The areas of the screen that have to be redrawn in the next frame.
+///
+/// Overlapping areas are merged into their union, so every pixel is redrawn
+/// at most once per rectangle.
+pub struct DirtyRegion {
+    rects: Vec<BoundingBox>,
+}
+
+impl DirtyRegion {
+    pub fn new() -> DirtyRegion {
+        DirtyRegion { rects: Vec::new() }
+    }
+
+    pub fn add(&mut self, bounding_box: &BoundingBox) {
+        let mut rect = bounding_box.with_outline();
+        if rect.is_empty() {
+            return;
+        }
+
+        // merging may create a rectangle that overlaps others again
+        let mut i = 0;
+        while i < self.rects.len() {
+            if self.rects[i].intersects(&rect) {
+                rect = rect.union(&self.rects.swap_remove(i));
+                i = 0;
+            } else {
+                i += 1;
+            }
+        }
+
+        if self.rects.len() >= MAX_RECTS {
+            for other in self.rects.drain(..) {
+                rect = rect.union(&other);
+            }
+        }
+
+        self.rects.push(rect);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    pub fn rects(&self) -> &[BoundingBox] {
+        &self.rects
+    }
+
+    /// Returns the dirty rectangles and resets the region.
+    pub fn take(&mut self) -> Vec<BoundingBox> {
+        mem::replace(&mut self.rects, Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bb(x: i32, y: i32, width: i32, height: i32) -> BoundingBox {
+        BoundingBox {
+            x: x,
+            y: y,
+            width: width,
+            height: height,
+        }
+    }
+
+    #[test]
+    fn merges_overlaps() {
+        let mut dirty = DirtyRegion::new();
+        dirty.add(&bb(0, 0, 10, 10));
+        dirty.add(&bb(50, 50, 10, 10));
+        dirty.add(&bb(5, 5, 10, 10));
+        assert_eq!(dirty.rects().len(), 2);
+        assert!(dirty.rects().contains(&bb(0, 0, 16, 16)));
+
+        dirty.add(&bb(10, 10, 45, 45));
+        assert_eq!(dirty.take(), vec![bb(0, 0, 61, 61)]);
+        assert!(dirty.is_empty());
+    }
+}
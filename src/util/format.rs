@@ -0,0 +1,84 @@
+//! Numbers as text for labels, e.g. counters and sensor readings.
+
+use collections::String;
+use core::fmt::Write;
+
+pub fn integer(value: i32) -> String {
+    let mut text = String::new();
+    let _ = write!(text, "{}", value);
+    text
+}
+
+/// `value` with `separator` between groups of three digits, e.g. `12 345`.
+pub fn grouped(value: i32, separator: char) -> String {
+    let digits = integer(value);
+    let (sign, digits) = if value < 0 {
+        digits.split_at(1)
+    } else {
+        digits.split_at(0)
+    };
+
+    let mut text = String::from(sign);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            text.push(separator);
+        }
+        text.push(digit);
+    }
+    text
+}
+
+/// A fixed point number with `decimals` digits after the point, e.g.
+/// `fixed(2315, 2)` is `23.15`.
+pub fn fixed(value: i32, decimals: u32) -> String {
+    let value = value as i64;
+
+    // beyond 18 decimals the scale overflows, it is larger than any value
+    // then
+    let scale = (0..decimals).fold(Some(1i64), |scale, _| scale.and_then(|s| s.checked_mul(10)));
+    let (whole, fraction) = match scale {
+        Some(scale) => (value.abs() / scale, value.abs() % scale),
+        None => (0, value.abs()),
+    };
+
+    let mut text = String::new();
+    if value < 0 {
+        text.push('-');
+    }
+    let _ = write!(text, "{}", whole);
+    if decimals > 0 {
+        let _ = write!(text, ".{:0width$}", fraction, width = decimals as usize);
+    }
+    text
+}
+
+/// `value` of `max` in whole percent, e.g. `percent(1, 3)` is `33%`.
+pub fn percent(value: i32, max: i32) -> String {
+    let percent = if max == 0 {
+        0
+    } else {
+        value as i64 * 100 / max as i64
+    };
+
+    let mut text = String::new();
+    let _ = write!(text, "{}%", percent);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(integer(-42), "-42");
+        assert_eq!(grouped(1234, ' '), "1 234");
+        assert_eq!(grouped(-1234567, '.'), "-1.234.567");
+        assert_eq!(fixed(2315, 2), "23.15");
+        assert_eq!(fixed(-5, 2), "-0.05");
+        assert_eq!(fixed(7, 0), "7");
+        assert_eq!(fixed(-5, 20), "-0.00000000000000000005");
+        assert_eq!(percent(1, 3), "33%");
+        assert_eq!(percent(1, 0), "0%");
+    }
+}
@@ -18,3 +18,27 @@ pub fn isqrt(num: u32) -> u32 {
     }
     res
 }
+
+/// Angle of the vector (x, y) in whole degrees from -180 to 180, 0 points
+/// along the x axis. Approximated with integers, off by less than a degree.
+pub fn atan2_deg(y: i32, x: i32) -> i32 {
+    if x == 0 && y == 0 {
+        return 0;
+    }
+
+    let (ax, ay) = (x.abs(), y.abs());
+    // atan(z) ~ 45z + 15.64z(1 - z) for 0 <= z <= 1, z scaled by 1024
+    let atan = |num: i32, den: i32| -> i32 {
+        let z = ((num as i64) * 1024 / den as i64) as i32;
+        (4500 * z + 1564 * z * (1024 - z) / 1024 + 51200) / 102400
+    };
+    let mut angle = if ax >= ay { atan(ay, ax) } else { 90 - atan(ax, ay) };
+
+    if x < 0 {
+        angle = 180 - angle;
+    }
+    if y < 0 {
+        angle = -angle;
+    }
+    angle
+}
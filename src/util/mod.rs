@@ -1,4 +1,5 @@
 pub mod bounding_box;
-pub mod sizes;
 pub mod layout_funcs;
 pub mod math;
+pub mod dirty_region;
+pub mod format;
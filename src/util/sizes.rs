@@ -1,4 +0,0 @@
-pub static MAX_X: i32 = 479;
-pub static MAX_Y: i32 = 271;
-pub static RES_X: i32 = 480;
-pub static RES_Y: i32 = 272;